@@ -147,7 +147,7 @@ impl ToTokens for Test {
                 use ::string_template_test::TemplateTestExt as _;
                 let mut #render_root = #static_template_name.#render_root();
                 #attributes1
-                assert_eq!(#expected, #render_root.render());
+                assert_eq!(#expected, #render_root.render_expect());
             }
 
             #[test]
@@ -158,7 +158,7 @@ impl ToTokens for Test {
                 let mut #render_root = ::string_template_test::get_template(&#dynamic_template_name,
                                                     stringify!( #render_root ));
                 #attributes2
-                assert_eq!(#expected, #render_root.render());
+                assert_eq!(#expected, #render_root.render_expect());
             }
         };
         tokens.extend(expanded);
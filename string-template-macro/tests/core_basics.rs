@@ -152,3 +152,436 @@ st_test! {
     attributes: {},
     expected: "load kewl\ndaddy;",
 }
+
+st_test! {
+    test_name: if_true,
+    render_root: t,
+    template_group: {
+        t(x) ::= "<if(x)>works<endif>"
+    },
+    attributes: {
+        "x": true,
+    },
+    expected: "works",
+}
+
+st_test! {
+    test_name: if_null,
+    render_root: t,
+    template_group: {
+        t(x) ::= "<if(x)>fail<endif>"
+    },
+    attributes: {},
+    expected: "",
+}
+
+st_test! {
+    test_name: if_empty_list,
+    render_root: t,
+    template_group: {
+        t(x) ::= "<if(x)>fail<else>works<endif>"
+    },
+    attributes: {
+        "x": { Vec::<u8>::new() },
+    },
+    expected: "works",
+}
+
+st_test! {
+    test_name: if_empty_string,
+    render_root: t,
+    template_group: {
+        t(x) ::= "<if(x)>works<endif>"
+    },
+    attributes: {
+        "x": "",
+    },
+    expected: "works",
+}
+
+st_test! {
+    test_name: if_not,
+    render_root: t,
+    template_group: {
+        t(x) ::= "<if(!x)>works<endif>"
+    },
+    attributes: {
+        "x": false,
+    },
+    expected: "works",
+}
+
+st_test! {
+    test_name: if_else,
+    render_root: t,
+    template_group: {
+        t(x) ::= "<if(x)>fail<else>works<endif>"
+    },
+    attributes: {
+        "x": false,
+    },
+    expected: "works",
+}
+
+st_test! {
+    test_name: else_if,
+    render_root: t,
+    template_group: {
+        t(x, y) ::= "<if(x)>fail<elseif(y)>works<else>fail<endif>"
+    },
+    attributes: {
+        "y": 1,
+    },
+    expected: "works",
+}
+
+st_test! {
+    test_name: else_if_all_false,
+    render_root: t,
+    template_group: {
+        t(x, y) ::= "<if(x)>fail<elseif(y)>fail<endif>"
+    },
+    attributes: {},
+    expected: "",
+}
+
+st_test! {
+    test_name: nested_if,
+    render_root: t,
+    template_group: {
+        t(x, y) ::= "<if(x)>a<if(y)>b<else>c<endif>d<endif>"
+    },
+    attributes: {
+        "x": true,
+    },
+    expected: "acd",
+}
+
+st_test! {
+    test_name: if_and_or,
+    render_root: t,
+    template_group: {
+        t(x, y, z) ::= "<if(x && y)>fail<endif><if(x || y)>a<endif><if(!(x && y) && (z || x))>b<endif>"
+    },
+    attributes: {
+        "x": true,
+        "y": false,
+    },
+    expected: "ab",
+}
+
+st_test! {
+    test_name: if_attribute_property,
+    render_root: t,
+    template_group: {
+        t(user) ::= "<if(user.name)>hi <user.name><endif>"
+    },
+    attributes: {
+        "user": { User { id: 1, name: "John" } },
+    },
+    expected: "hi John",
+}
+
+st_test! {
+    test_name: if_include,
+    render_root: t,
+    template_group: {
+        t() ::= "<if(b())>works<endif>"
+        b() ::= ""
+    },
+    attributes: {},
+    expected: "works",
+}
+
+st_test! {
+    test_name: include_with_positional_arguments,
+    render_root: t,
+    template_group: {
+        t(x, user) ::= r#"<b(x, "lit", user.name)>"#
+        b(first, second, third) ::= "<first>, <second>, <third>"
+    },
+    attributes: {
+        "x": 1,
+        "user": { User { id: 2, name: "Ter" } },
+    },
+    expected: "1, lit, Ter",
+}
+
+st_test! {
+    test_name: include_with_named_arguments,
+    render_root: t,
+    template_group: {
+        t(a, b) ::= "<pair(y=b, x=a)>"
+        pair(x, y) ::= "<x>:<y>"
+    },
+    attributes: {
+        "a": "Ter",
+        "b": "Tom",
+    },
+    expected: "Ter:Tom",
+}
+
+st_test! {
+    test_name: include_with_template_arguments,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<b(names:{n | [<n>]}, c())>"
+        b(x, y) ::= "<x><y>"
+        c() ::= "!"
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom"] },
+    },
+    expected: "[Ter][Tom]!",
+}
+
+st_test! {
+    test_name: map_template_with_arguments,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<names:surround("(", ")")>"#
+        surround(x, left, right) ::= "<left><x><right>"
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom"] },
+    },
+    expected: "(Ter)(Tom)",
+}
+
+st_test! {
+    test_name: include_sees_enclosing_attributes,
+    render_root: t,
+    template_group: {
+        t(x) ::= "<b()>"
+        b() ::= "<x>!"
+    },
+    attributes: {
+        "x": "Ter",
+    },
+    expected: "Ter!",
+}
+
+st_test! {
+    test_name: include_sees_attributes_through_several_templates,
+    render_root: t,
+    template_group: {
+        t(x) ::= "<b()>"
+        b() ::= "<c()>"
+        c() ::= "<x>!"
+    },
+    attributes: {
+        "x": "Ter",
+    },
+    expected: "Ter!",
+}
+
+st_test! {
+    test_name: include_arguments_shadow_enclosing_attributes,
+    render_root: t,
+    template_group: {
+        t(x, y) ::= r#"<b("Tom")>"#
+        b(x) ::= "<x>, <y>"
+    },
+    attributes: {
+        "x": "Ter",
+        "y": "Sumana",
+    },
+    expected: "Tom, Sumana",
+}
+
+st_test! {
+    test_name: unset_formal_arguments_shadow_enclosing_attributes,
+    render_root: t,
+    template_group: {
+        t(x, y) ::= r#"<b(y="Tom")>"#
+        b(x, y) ::= "[<x>], <y>"
+    },
+    attributes: {
+        "x": "Ter",
+    },
+    expected: "[], Tom",
+}
+
+st_test! {
+    test_name: mapped_template_sees_enclosing_attributes,
+    render_root: t,
+    template_group: {
+        t(names, prefix) ::= "<names:prefixed()>"
+        prefixed(name) ::= "<prefix><name> "
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom"] },
+        "prefix": "@",
+    },
+    expected: "@Ter @Tom ",
+}
+
+st_test! {
+    test_name: pass_through_arguments,
+    render_root: t,
+    template_group: {
+        t(x, y, z) ::= "<b(...)>"
+        b(x, y) ::= "<x>, <y>, <z>"
+    },
+    attributes: {
+        "x": "Ter",
+        "y": "Tom",
+        "z": "Sumana",
+    },
+    expected: "Ter, Tom, Sumana",
+}
+
+st_test! {
+    test_name: pass_through_with_named_arguments,
+    render_root: t,
+    template_group: {
+        t(x, y) ::= r#"<b(x="Sumana", ...)>"#
+        b(x, y) ::= "<x>, <y>"
+    },
+    attributes: {
+        "x": "Ter",
+        "y": "Tom",
+    },
+    expected: "Sumana, Tom",
+}
+
+st_test! {
+    test_name: pass_through_from_anonymous_template,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<names:{name | <b(...)>}>"
+        b(name) ::= "[<name>]"
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom"] },
+    },
+    expected: "[Ter][Tom]",
+}
+
+st_test! {
+    test_name: default_string_argument,
+    render_root: t,
+    template_group: {
+        t(x, sep=", ") ::= "<x; separator=sep>"
+    },
+    attributes: {
+        "x": { vec!["Ter", "Tom"] },
+    },
+    expected: "Ter, Tom",
+}
+
+st_test! {
+    test_name: default_argument_overridden_by_attribute,
+    render_root: t,
+    template_group: {
+        t(x="Ter") ::= "hi <x>!"
+    },
+    attributes: {
+        "x": "Tom",
+    },
+    expected: "hi Tom!",
+}
+
+st_test! {
+    test_name: default_boolean_argument,
+    render_root: t,
+    template_group: {
+        t(flag=true, other=false) ::= "<if(flag)>yes<endif><if(other)>no<endif>"
+    },
+    attributes: {},
+    expected: "yes",
+}
+
+st_test! {
+    test_name: default_list_argument,
+    render_root: t,
+    template_group: {
+        t(names=["Ter", "Tom"]) ::= r#"<names; separator=", ">"#
+    },
+    attributes: {},
+    expected: "Ter, Tom",
+}
+
+st_test! {
+    test_name: default_anonymous_template_argument,
+    render_root: t,
+    template_group: {
+        t(x, body={"<x>!"}) ::= "[<body>]"
+    },
+    attributes: {
+        "x": "Ter",
+    },
+    expected: "[Ter!]",
+}
+
+st_test! {
+    test_name: default_anonymous_template_argument_keeps_whitespace,
+    render_root: t,
+    template_group: {
+        t(x, y, body={"<x> and <y>"}) ::= "[<body>]"
+    },
+    attributes: {
+        "x": "Ter",
+        "y": "Tom",
+    },
+    expected: "[Ter and Tom]",
+}
+
+st_test! {
+    test_name: default_arguments_fill_missing_positional_arguments,
+    render_root: t,
+    template_group: {
+        t() ::= r#"<b("Ter")>"#
+        b(x, y="Tom") ::= "<x>, <y>"
+    },
+    attributes: {},
+    expected: "Ter, Tom",
+}
+
+st_test! {
+    test_name: tabs_and_non_ascii_literal_text,
+    render_root: t,
+    template_group: {
+        t(x) ::= "a\tb <x>, héllo <x>"
+    },
+    attributes: {
+        "x": "wörld",
+    },
+    expected: "a\tb wörld, héllo wörld",
+}
+
+st_test! {
+    test_name: conditional_tags_alone_on_their_lines,
+    render_root: t,
+    template_group: {
+        t(x) ::= "begin\n<if(x)>\n  yes\n<else>\n  no\n<endif>\nend"
+    },
+    attributes: {
+        "x": true,
+    },
+    expected: "begin\n  yes\nend",
+}
+
+st_test! {
+    test_name: indented_conditional_tags_alone_on_their_lines,
+    render_root: t,
+    template_group: {
+        t(x, y) ::= "begin\n  <if(x)>\n    x\n  <elseif(y)>\n    <if(y)>\n    y\n    <endif>\n  <endif>\nend"
+    },
+    attributes: {
+        "y": true,
+    },
+    expected: "begin\n    y\nend",
+}
+
+st_test! {
+    test_name: conditional_tags_sharing_lines,
+    render_root: t,
+    template_group: {
+        t(x) ::= "a <if(x)>b<endif> c\n<if(x)>d\n<endif>e"
+    },
+    attributes: {
+        "x": true,
+    },
+    expected: "a b c\nd\ne",
+}
@@ -0,0 +1,56 @@
+use string_template_macro::st_test;
+
+st_test! {
+    test_name: render_with_dollar_delimiters,
+    render_root: t,
+    template_group: {
+        delimiters "$", "$"
+        t(name) ::= "<b>$name$</b>"
+    },
+    attributes: {
+        "name": "Ter",
+    },
+    expected: "<b>Ter</b>",
+}
+
+st_test! {
+    test_name: render_nested_templates_with_custom_delimiters,
+    render_root: t,
+    template_group: {
+        delimiters "%", "%"
+        t(tys, x) ::= "$%tys:{ty | Vec<%ty%>}; separator=\", \"%%if(x)% <x>%endif%"
+    },
+    attributes: {
+        "tys": "u8",
+        "tys": "str",
+        "x": true,
+    },
+    expected: "$Vec<u8>, Vec<str> <x>",
+}
+
+st_test! {
+    test_name: render_comments_and_escapes_with_custom_delimiters,
+    render_root: t,
+    template_group: {
+        delimiters "[", "]"
+        t(x) ::= r#"[! a comment !]\[[x]][\n]<x>[u()]"#
+        u() ::= "[\\ ]done"
+    },
+    attributes: {
+        "x": "y",
+    },
+    expected: "[y]\n<x> done",
+}
+
+st_test! {
+    test_name: render_escapes_after_start_of_default_delimiters,
+    render_root: t,
+    template_group: {
+        delimiters "$", "$"
+        t(x) ::= "if a < b\nthen $x$"
+    },
+    attributes: {
+        "x": "X",
+    },
+    expected: "if a < b\nthen X",
+}
@@ -0,0 +1,117 @@
+use string_template_macro::st_test;
+
+st_test! {
+    test_name: dictionary,
+    render_root: t,
+    template_group: {
+        typeInit ::= [ "int":"0", "float":"0.0" ]
+        t(kind, name) ::= "<kind> <name> = <typeInit.int>;"
+    },
+    attributes: {
+        "kind": "int",
+        "name": "x",
+    },
+    expected: "int x = 0;",
+}
+
+st_test! {
+    test_name: dictionary_missing_key,
+    render_root: t,
+    template_group: {
+        typeInit ::= [ "int":"0", "float":"0.0" ]
+        t() ::= "[<typeInit.double>]"
+    },
+    attributes: {},
+    expected: "[]",
+}
+
+st_test! {
+    test_name: dictionary_default_value,
+    render_root: t,
+    template_group: {
+        typeInit ::= [ "int":"0", default:"null" ]
+        t() ::= "<typeInit.double>"
+    },
+    attributes: {},
+    expected: "null",
+}
+
+st_test! {
+    test_name: dictionary_default_key,
+    render_root: t,
+    template_group: {
+        typeInit ::= [ "int":"0", default:key ]
+        t() ::= "<typeInit.double>"
+    },
+    attributes: {},
+    expected: "double",
+}
+
+st_test! {
+    test_name: dictionary_key_value,
+    render_root: t,
+    template_group: {
+        types ::= [ "int":key ]
+        t() ::= "<types.int>"
+    },
+    attributes: {},
+    expected: "int",
+}
+
+st_test! {
+    test_name: dictionary_boolean_value,
+    render_root: t,
+    template_group: {
+        isNumeric ::= [ "int":true, "string":false ]
+        t() ::= "<if(isNumeric.int)>int<endif><if(isNumeric.string)>string<endif>"
+    },
+    attributes: {},
+    expected: "int",
+}
+
+st_test! {
+    test_name: dictionary_list_value,
+    render_root: t,
+    template_group: {
+        values ::= [ "names":["Ter", "Tom"] ]
+        t() ::= r#"<values.names; separator=", ">"#
+    },
+    attributes: {},
+    expected: "Ter, Tom",
+}
+
+st_test! {
+    test_name: dictionary_template_value,
+    render_root: t,
+    template_group: {
+        typeInit ::= [ "int":{"0<w>"}, default:"null" ]
+        t(w) ::= "<typeInit.int>"
+    },
+    attributes: {
+        "w": "L",
+    },
+    expected: "0L",
+}
+
+st_test! {
+    test_name: dictionary_visible_in_included_template,
+    render_root: t,
+    template_group: {
+        typeInit ::= [ "int":"0" ]
+        t() ::= "<b()>"
+        b() ::= "<typeInit.int>"
+    },
+    attributes: {},
+    expected: "0",
+}
+
+st_test! {
+    test_name: attribute_shadows_dictionary,
+    render_root: t,
+    template_group: {
+        typeInit ::= [ "int":"0" ]
+        t(typeInit) ::= "[<typeInit.int>]"
+    },
+    attributes: {},
+    expected: "[]",
+}
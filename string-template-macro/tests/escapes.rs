@@ -0,0 +1,68 @@
+use string_template_macro::st_test;
+
+st_test! {
+    test_name: strip_comment,
+    render_root: t,
+    template_group: {
+        t(name) ::= "Hello<! the name goes here !>, <name>!"
+    },
+    attributes: {
+        "name": "Ter",
+    },
+    expected: "Hello, Ter!",
+}
+
+st_test! {
+    test_name: strip_multi_line_comment,
+    render_root: t,
+    template_group: {
+        t() ::= "a<! one\n<x>\ntwo !>b"
+    },
+    attributes: {},
+    expected: "ab",
+}
+
+st_test! {
+    test_name: render_special_characters,
+    render_root: t,
+    template_group: {
+        t() ::= r#"a<\n>b<\t>c<\ >d<\n\n>e"#
+    },
+    attributes: {},
+    expected: "a\nb\tc d\n\ne",
+}
+
+st_test! {
+    test_name: render_unicode_character,
+    render_root: t,
+    template_group: {
+        t() ::= r#"<\u00e9><\u2603>"#
+    },
+    attributes: {},
+    expected: "é☃",
+}
+
+st_test! {
+    test_name: render_escaped_delimiter,
+    render_root: t,
+    template_group: {
+        t(ty) ::= r#"Vec\<<ty>>"#
+    },
+    attributes: {
+        "ty": "u8",
+    },
+    expected: "Vec<u8>",
+}
+
+st_test! {
+    test_name: render_escaped_delimiter_in_anonymous_template,
+    render_root: t,
+    template_group: {
+        t(tys) ::= r#"<tys:{ty | Box\<<ty>>}; separator=", ">"#
+    },
+    attributes: {
+        "tys": "u8",
+        "tys": "str",
+    },
+    expected: "Box<u8>, Box<str>",
+}
@@ -0,0 +1,198 @@
+use serde_derive::Serialize;
+
+use string_template_macro::st_test;
+
+#[derive(Serialize)]
+struct User {
+    names: Vec<&'static str>,
+}
+
+st_test! {
+    test_name: first,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<first(names)>"
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom", "Sumana"] },
+    },
+    expected: "Ter",
+}
+
+st_test! {
+    test_name: first_of_single_value,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<first(names)>"
+    },
+    attributes: {
+        "names": "Ter",
+    },
+    expected: "Ter",
+}
+
+st_test! {
+    test_name: last,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<last(names)>"
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom", "Sumana"] },
+    },
+    expected: "Sumana",
+}
+
+st_test! {
+    test_name: rest,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<rest(names); separator=", ">"#
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom", "Sumana"] },
+    },
+    expected: "Tom, Sumana",
+}
+
+st_test! {
+    test_name: rest_of_single_value,
+    render_root: t,
+    template_group: {
+        t(names) ::= "[<rest(names)>]"
+    },
+    attributes: {
+        "names": "Ter",
+    },
+    expected: "[]",
+}
+
+st_test! {
+    test_name: trunc,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<trunc(names); separator=", ">"#
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom", "Sumana"] },
+    },
+    expected: "Ter, Tom",
+}
+
+st_test! {
+    test_name: length,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<length(names)>"
+    },
+    attributes: {
+        "names": { vec![Some("Ter"), None, Some("Sumana")] },
+    },
+    expected: "3",
+}
+
+st_test! {
+    test_name: length_of_null,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<length(names)>"
+    },
+    attributes: {},
+    expected: "0",
+}
+
+st_test! {
+    test_name: reverse,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<reverse(names); separator=", ">"#
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom", "Sumana"] },
+    },
+    expected: "Sumana, Tom, Ter",
+}
+
+st_test! {
+    test_name: strip,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<strip(names); null="n/a", separator=", ">"#
+    },
+    attributes: {
+        "names": { vec![Some("Ter"), None, Some("Sumana")] },
+    },
+    expected: "Ter, Sumana",
+}
+
+st_test! {
+    test_name: trim,
+    render_root: t,
+    template_group: {
+        t(name) ::= "[<trim(name)>]"
+    },
+    attributes: {
+        "name": "  Ter \n",
+    },
+    expected: "[Ter]",
+}
+
+st_test! {
+    test_name: strlen,
+    render_root: t,
+    template_group: {
+        t(name) ::= "<strlen(name)>"
+    },
+    attributes: {
+        "name": "Sumana",
+    },
+    expected: "6",
+}
+
+st_test! {
+    test_name: function_of_attribute_path,
+    render_root: t,
+    template_group: {
+        t(user) ::= "<first(user.names)>"
+    },
+    attributes: {
+        "user": { User { names: vec!["Ter", "Tom"] } },
+    },
+    expected: "Ter",
+}
+
+st_test! {
+    test_name: map_function_result,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<rest(names):{n | [<n>]}>"
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom", "Sumana"] },
+    },
+    expected: "[Tom][Sumana]",
+}
+
+st_test! {
+    test_name: function_of_mapped_list,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<last(names:{n | [<n>]})>"
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom", "Sumana"] },
+    },
+    expected: "[Sumana]",
+}
+
+st_test! {
+    test_name: function_in_condition,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<if(rest(names))>many<else>one<endif>"
+    },
+    attributes: {
+        "names": { vec!["Ter"] },
+    },
+    expected: "one",
+}
@@ -0,0 +1,28 @@
+use string_template_macro::st_test;
+
+st_test! {
+    test_name: import_group_file,
+    render_root: t,
+    template_group: {
+        import "../string-template-test/fixtures/base.stg"
+        t(name) ::= "<method(name)>"
+        visibility() ::= "pub"
+    },
+    attributes: {
+        "name": "f",
+    },
+    expected: "pub fn f() { todo!() }",
+}
+
+st_test! {
+    test_name: import_group_file_importing_group_files,
+    render_root: t,
+    template_group: {
+        import "../string-template-test/fixtures/dialect.stg"
+        t(name) ::= "<method(name)>"
+    },
+    attributes: {
+        "name": "f",
+    },
+    expected: "pub fn f() { todo!() // generated }",
+}
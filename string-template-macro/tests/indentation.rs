@@ -0,0 +1,74 @@
+use string_template_macro::st_test;
+
+st_test! {
+    test_name: indent_included_template,
+    render_root: t,
+    template_group: {
+        t() ::= "begin\n  <u()>\nend"
+        u() ::= "kewl\ndaddy"
+    },
+    attributes: {},
+    expected: "begin\n  kewl\n  daddy\nend",
+}
+
+st_test! {
+    test_name: indent_list_with_newline_separator,
+    render_root: t,
+    template_group: {
+        t(names) ::= "  <names; separator=\"\\n\">"
+    },
+    attributes: {
+        "names": "Ter",
+        "names": "Tom",
+    },
+    expected: "  Ter\n  Tom",
+}
+
+st_test! {
+    test_name: indent_nested_includes,
+    render_root: t,
+    template_group: {
+        t() ::= "{\n  <u()>\n}"
+        u() ::= "if (x) {\n    <v()>\n}"
+        v() ::= "a();\nb();"
+    },
+    attributes: {},
+    expected: "{\n  if (x) {\n      a();\n      b();\n  }\n}",
+}
+
+st_test! {
+    test_name: indent_inside_conditional,
+    render_root: t,
+    template_group: {
+        t(x) ::= "<if(x)>\n  <u()>\n<endif>"
+        u() ::= "kewl\ndaddy"
+    },
+    attributes: {
+        "x": true,
+    },
+    expected: "  kewl\n  daddy\n",
+}
+
+st_test! {
+    test_name: indent_mapped_templates,
+    render_root: t,
+    template_group: {
+        t(names) ::= "names:\n    <names:{n | <n>\n}>"
+    },
+    attributes: {
+        "names": "Ter",
+        "names": "Tom",
+    },
+    expected: "names:\n    Ter\n    Tom\n",
+}
+
+st_test! {
+    test_name: no_indent_after_text_on_line,
+    render_root: t,
+    template_group: {
+        t() ::= "x <u()>"
+        u() ::= "kewl\ndaddy"
+    },
+    attributes: {},
+    expected: "x kewl\ndaddy",
+}
@@ -0,0 +1,141 @@
+use serde_derive::Serialize;
+
+use string_template_macro::st_test;
+
+#[derive(Serialize)]
+struct Node {
+    kind: &'static str,
+    name: &'static str,
+}
+
+#[derive(Serialize)]
+struct Pair {
+    left: Node,
+    right: Node,
+}
+
+st_test! {
+    test_name: include_template_named_by_attribute,
+    render_root: t,
+    template_group: {
+        t(kind, x) ::= "<(kind)(x)>"
+        bold(x) ::= "*<x>*"
+    },
+    attributes: {
+        "kind": "bold",
+        "x": "hi",
+    },
+    expected: "*hi*",
+}
+
+st_test! {
+    test_name: include_template_named_by_property,
+    render_root: t,
+    template_group: {
+        t(nodes) ::= "<nodes:{n | <(n.kind)(n)>}; separator=\", \">"
+        function(node) ::= "fn <node.name>()"
+        module(node) ::= "mod <node.name>"
+    },
+    attributes: {
+        "nodes": { Node { kind: "module", name: "a" } },
+        "nodes": { Node { kind: "function", name: "b" } },
+    },
+    expected: "mod a, fn b()",
+}
+
+st_test! {
+    test_name: include_template_named_by_string,
+    render_root: t,
+    template_group: {
+        t() ::= "<(\"u\")()>"
+        u() ::= "u"
+    },
+    attributes: {},
+    expected: "u",
+}
+
+st_test! {
+    test_name: include_missing_template_named_by_attribute,
+    render_root: t,
+    template_group: {
+        t(kind) ::= "[<(kind)()>]"
+    },
+    attributes: {
+        "kind": "missing",
+    },
+    expected: "[]",
+}
+
+st_test! {
+    test_name: map_template_named_by_attribute,
+    render_root: t,
+    template_group: {
+        t(names, kind) ::= "<names:(kind)(); separator=\" \">"
+        quoted(x) ::= "'<x>'"
+    },
+    attributes: {
+        "names": "Ter",
+        "names": "Tom",
+        "kind": "quoted",
+    },
+    expected: "'Ter' 'Tom'",
+}
+
+st_test! {
+    test_name: property_named_by_attribute,
+    render_root: t,
+    template_group: {
+        t(node, property) ::= "<node.(property)>"
+    },
+    attributes: {
+        "node": { Node { kind: "function", name: "f" } },
+        "property": "name",
+    },
+    expected: "f",
+}
+
+st_test! {
+    test_name: dictionary_entry_named_by_attribute,
+    render_root: t,
+    template_group: {
+        typeInit ::= [ "int":"0", default:"null" ]
+        t(types) ::= "<types:{ty | <typeInit.(ty)>}; separator=\", \">"
+    },
+    attributes: {
+        "types": "int",
+        "types": "String",
+    },
+    expected: "0, null",
+}
+
+st_test! {
+    test_name: property_of_indirect_property,
+    render_root: t,
+    template_group: {
+        t(pair, side) ::= "<pair.(side).name>"
+    },
+    attributes: {
+        "pair": {
+            Pair {
+                left: Node { kind: "function", name: "l" },
+                right: Node { kind: "function", name: "r" },
+            }
+        },
+        "side": "right",
+    },
+    expected: "r",
+}
+
+st_test! {
+    test_name: conditional_on_indirect_include,
+    render_root: t,
+    template_group: {
+        t(kind) ::= "<if((kind)())>yes<else>no<endif>"
+        empty() ::= ""
+        full() ::= "x"
+    },
+    attributes: {
+        "kind": "full",
+    },
+    expected: "yes",
+}
@@ -0,0 +1,96 @@
+use string_template_macro::st_test;
+
+st_test! {
+    test_name: list_literal,
+    render_root: t,
+    template_group: {
+        t(b) ::= "<[\"a\", b, \"c\"]; separator=\", \">"
+    },
+    attributes: {
+        "b": "B",
+    },
+    expected: "a, B, c",
+}
+
+st_test! {
+    test_name: list_literal_flattens_lists,
+    render_root: t,
+    template_group: {
+        t(names, more) ::= "<[names, \"x\", more, []]; separator=\" \">"
+    },
+    attributes: {
+        "names": "Ter",
+        "names": "Tom",
+        "more": "Sri",
+    },
+    expected: "Ter Tom x Sri",
+}
+
+st_test! {
+    test_name: map_over_list_literal,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<[\"Kay\", names]:{n | [<n>]}>"
+    },
+    attributes: {
+        "names": "Ter",
+        "names": "Tom",
+    },
+    expected: "[Kay][Ter][Tom]",
+}
+
+st_test! {
+    test_name: list_literal_as_argument,
+    render_root: t,
+    template_group: {
+        t(a) ::= "<u([a, \"b\"])>"
+        u(xs) ::= "<length(xs)>: <xs; separator=\"+\">"
+    },
+    attributes: {
+        "a": "a",
+    },
+    expected: "2: a+b",
+}
+
+st_test! {
+    test_name: list_literal_in_function,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<last([\"x\", names])>"
+    },
+    attributes: {
+        "names": "Ter",
+        "names": "Tom",
+    },
+    expected: "Tom",
+}
+
+st_test! {
+    test_name: empty_list_literal_is_false,
+    render_root: t,
+    template_group: {
+        t() ::= "<if([])>yes<else>no<endif>"
+    },
+    attributes: {},
+    expected: "no",
+}
+
+st_test! {
+    test_name: string_literal,
+    render_root: t,
+    template_group: {
+        t() ::= "<\"hi\\tthere\">"
+    },
+    attributes: {},
+    expected: "hi\tthere",
+}
+
+st_test! {
+    test_name: map_over_string_literal,
+    render_root: t,
+    template_group: {
+        t() ::= "<\"x\":{s | (<s>)}>"
+    },
+    attributes: {},
+    expected: "(x)",
+}
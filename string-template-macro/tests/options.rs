@@ -0,0 +1,158 @@
+use string_template_macro::st_test;
+
+st_test! {
+    test_name: separator,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<names; separator=", ">"#
+    },
+    attributes: {
+        "names": "Ter",
+        "names": "Tom",
+        "names": "Sumana",
+    },
+    expected: "Ter, Tom, Sumana",
+}
+
+st_test! {
+    test_name: separator_from_attribute,
+    render_root: t,
+    template_group: {
+        t(names, sep) ::= "<names; separator=sep>"
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom"] },
+        "sep": " and ",
+    },
+    expected: "Ter and Tom",
+}
+
+st_test! {
+    test_name: separator_with_escapes,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<names; separator="\n\t\"">"#
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom"] },
+    },
+    expected: "Ter\n\t\"Tom",
+}
+
+st_test! {
+    test_name: separator_with_single_value,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<names; separator=", ">"#
+    },
+    attributes: {
+        "names": "Ter",
+    },
+    expected: "Ter",
+}
+
+st_test! {
+    test_name: separator_skips_null_values,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<names; separator=", ">"#
+    },
+    attributes: {
+        "names": { vec![Some("Ter"), None, Some("Tom"), None] },
+    },
+    expected: "Ter, Tom",
+}
+
+st_test! {
+    test_name: null_option,
+    render_root: t,
+    template_group: {
+        t(name) ::= r#"<name; null="n/a">"#
+    },
+    attributes: {},
+    expected: "n/a",
+}
+
+st_test! {
+    test_name: null_option_in_list,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<names; null="n/a", separator=", ">"#
+    },
+    attributes: {
+        "names": { vec![Some("Ter"), None, Some("Tom"), None] },
+    },
+    expected: "Ter, n/a, Tom, n/a",
+}
+
+st_test! {
+    test_name: separator_with_map,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<names:{n | [<n>]}; separator=", ">"#
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom"] },
+    },
+    expected: "[Ter], [Tom]",
+}
+
+st_test! {
+    test_name: format_upper,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<names; format="upper", separator=" ">"#
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom"] },
+    },
+    expected: "TER TOM",
+}
+
+st_test! {
+    test_name: format_cap,
+    render_root: t,
+    template_group: {
+        t(name) ::= r#"<name; format="cap">"#
+    },
+    attributes: {
+        "name": "ter",
+    },
+    expected: "Ter",
+}
+
+st_test! {
+    test_name: format_xml_encode,
+    render_root: t,
+    template_group: {
+        t(name) ::= r#"<name; format="xml-encode">"#
+    },
+    attributes: {
+        "name": "<a href=\"x\">&</a>",
+    },
+    expected: "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;",
+}
+
+st_test! {
+    test_name: format_url_encode,
+    render_root: t,
+    template_group: {
+        t(name) ::= r#"<name; format="url-encode">"#
+    },
+    attributes: {
+        "name": "a b&c",
+    },
+    expected: "a+b%26c",
+}
+
+st_test! {
+    test_name: format_ignored_for_template_output,
+    render_root: t,
+    template_group: {
+        t(name) ::= r#"<name:{n | <n>!}; format="upper">"#
+    },
+    attributes: {
+        "name": "ter",
+    },
+    expected: "ter!",
+}
@@ -0,0 +1,104 @@
+use string_template_macro::st_test;
+
+st_test! {
+    test_name: embedded_region_renders_default,
+    render_root: t,
+    template_group: {
+        t() ::= "a<@r>default<@end>b"
+    },
+    attributes: {},
+    expected: "adefaultb",
+}
+
+st_test! {
+    test_name: implicit_region_renders_nothing_by_default,
+    render_root: t,
+    template_group: {
+        t() ::= "a<@r()>b"
+    },
+    attributes: {},
+    expected: "ab",
+}
+
+st_test! {
+    test_name: region_definition_overrides_implicit_region,
+    render_root: t,
+    template_group: {
+        t() ::= "a<@r()>b"
+        @t.r() ::= "foo"
+    },
+    attributes: {},
+    expected: "afoob",
+}
+
+st_test! {
+    test_name: region_definition_overrides_embedded_region,
+    render_root: t,
+    template_group: {
+        t() ::= "a<@r>default<@end>b"
+        @t.r() ::= "foo"
+    },
+    attributes: {},
+    expected: "afoob",
+}
+
+st_test! {
+    test_name: regions_see_attributes_of_template,
+    render_root: t,
+    template_group: {
+        t(x) ::= "<@r><x><@end>|<@s()>"
+        @t.s() ::= "[<x>]"
+    },
+    attributes: {
+        "x": "y",
+    },
+    expected: "y|[y]",
+}
+
+st_test! {
+    test_name: regions_are_named_after_their_template,
+    render_root: t,
+    template_group: {
+        t() ::= "<@r()>-<u()>"
+        u() ::= "<@r()>"
+        @u.r() ::= "u"
+    },
+    attributes: {},
+    expected: "-u",
+}
+
+st_test! {
+    test_name: embedded_region_drops_newlines_inside_tags,
+    render_root: t,
+    template_group: {
+        t() ::= "begin\n<@r>\n  body\n<@end>\nend"
+    },
+    attributes: {},
+    expected: "begin\n  body\nend",
+}
+
+st_test! {
+    test_name: region_in_conditional,
+    render_root: t,
+    template_group: {
+        t(x) ::= "<if(x)><@r>yes<@end><endif>"
+    },
+    attributes: {
+        "x": true,
+    },
+    expected: "yes",
+}
+
+st_test! {
+    test_name: region_in_mapped_subtemplate,
+    render_root: t,
+    template_group: {
+        t(x) ::= "<x:{n|<@r()>}>"
+        @t.r() ::= "R"
+        r() ::= "WRONG"
+    },
+    attributes: {
+        "x": ["a", "b"],
+    },
+    expected: "RR",
+}
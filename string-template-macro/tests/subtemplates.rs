@@ -0,0 +1,356 @@
+use serde_derive::Serialize;
+
+use string_template_macro::st_test;
+
+st_test! {
+    test_name: map_anonymous_template,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<names:{n | [<n>]}>"
+    },
+    attributes: {
+        "names": "Ter",
+        "names": "Tom",
+    },
+    expected: "[Ter][Tom]",
+}
+
+st_test! {
+    test_name: map_anonymous_template_with_implicit_parameter,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<names:{[<it>]}>"
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom"] },
+    },
+    expected: "[Ter][Tom]",
+}
+
+st_test! {
+    test_name: map_single_value,
+    render_root: t,
+    template_group: {
+        t(name) ::= "<name:{n | [<n>]}>"
+    },
+    attributes: {
+        "name": "Ter",
+    },
+    expected: "[Ter]",
+}
+
+st_test! {
+    test_name: map_null_value,
+    render_root: t,
+    template_group: {
+        t(name) ::= "<name:{n | [<n>]}>"
+    },
+    attributes: {},
+    expected: "",
+}
+
+st_test! {
+    test_name: map_named_template,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<names:bold()>"
+        bold(x) ::= "*<x>*"
+    },
+    attributes: {
+        "names": "Ter",
+        "names": "Tom",
+    },
+    expected: "*Ter**Tom*",
+}
+
+st_test! {
+    test_name: chained_maps,
+    render_root: t,
+    template_group: {
+        t(names) ::= "<names:bold():{b | (<b>)}:bold()>"
+        bold(x) ::= "*<x>*"
+    },
+    attributes: {
+        "names": "Ter",
+        "names": "Tom",
+    },
+    expected: "*(*Ter*)**(*Tom*)*",
+}
+
+#[derive(Serialize)]
+struct User {
+    id: u8,
+    name: &'static str,
+}
+
+st_test! {
+    test_name: map_with_property_reference,
+    render_root: t,
+    template_group: {
+        t(users) ::= "<users:{u | <u.id>=<u.name>;}>"
+    },
+    attributes: {
+        "users": { User { id: 1, name: "Ter" } },
+        "users": { User { id: 2, name: "Tom" } },
+    },
+    expected: "1=Ter;2=Tom;",
+}
+
+st_test! {
+    test_name: anonymous_template_sees_enclosing_attributes,
+    render_root: t,
+    template_group: {
+        t(names, greeting) ::= "<names:{n | <greeting> <n>! }>"
+    },
+    attributes: {
+        "names": "Ter",
+        "names": "Tom",
+        "greeting": "hi",
+    },
+    expected: "hi Ter! hi Tom! ",
+}
+
+st_test! {
+    test_name: nested_anonymous_templates,
+    render_root: t,
+    template_group: {
+        t(xs, ys) ::= "<xs:{x | <ys:{y | <x><y> }>}>"
+    },
+    attributes: {
+        "xs": { vec!["a", "b"] },
+        "ys": { vec![1, 2] },
+    },
+    expected: "a1 a2 b1 b2 ",
+}
+
+st_test! {
+    test_name: conditional_in_anonymous_template,
+    render_root: t,
+    template_group: {
+        t(names, x) ::= "<names:{n | <if(x)>(<n>)<else><n><endif>}>"
+    },
+    attributes: {
+        "names": "Ter",
+        "names": "Tom",
+        "x": true,
+    },
+    expected: "(Ter)(Tom)",
+}
+
+st_test! {
+    test_name: iteration_index,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<names:{n | <i>. <n>}; separator=", ">"#
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom", "Sumana"] },
+    },
+    expected: "1. Ter, 2. Tom, 3. Sumana",
+}
+
+st_test! {
+    test_name: zero_based_iteration_index,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<names:{n | <i0>:<n>}; separator=", ">"#
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom", "Sumana"] },
+    },
+    expected: "0:Ter, 1:Tom, 2:Sumana",
+}
+
+st_test! {
+    test_name: iteration_index_skips_nulls,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<names:{n | <i>. <n>}; separator=", ">"#
+    },
+    attributes: {
+        "names": { vec![Some("Ter"), None, Some("Sumana")] },
+    },
+    expected: "1. Ter, 2. Sumana",
+}
+
+st_test! {
+    test_name: iteration_index_in_named_template,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<names:row(); separator=", ">"#
+        row(name) ::= "<i>. <name>"
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom"] },
+    },
+    expected: "1. Ter, 2. Tom",
+}
+
+st_test! {
+    test_name: iteration_index_of_single_value,
+    render_root: t,
+    template_group: {
+        t(name) ::= "<name:{n | <i>. <n>}>"
+    },
+    attributes: {
+        "name": "Ter",
+    },
+    expected: "1. Ter",
+}
+
+st_test! {
+    test_name: nested_iteration_index,
+    render_root: t,
+    template_group: {
+        t(rows, columns) ::= r#"<rows:{r | <columns:{c | <i>}>}; separator=", ">"#
+    },
+    attributes: {
+        "rows": { vec!["a", "b"] },
+        "columns": { vec!["x", "y"] },
+    },
+    expected: "12, 12",
+}
+
+st_test! {
+    test_name: iteration_index_in_chained_map,
+    render_root: t,
+    template_group: {
+        t(names) ::= r#"<names:{n | <n>}:{n | <i>=<n>}; separator=", ">"#
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom"] },
+    },
+    expected: "1=Ter, 2=Tom",
+}
+
+st_test! {
+    test_name: parallel_map,
+    render_root: t,
+    template_group: {
+        t(names, phones) ::= r#"<names, phones:{n, p | <n>:<p>}; separator=", ">"#
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom"] },
+        "phones": { vec!["x5001", "x5002"] },
+    },
+    expected: "Ter:x5001, Tom:x5002",
+}
+
+st_test! {
+    test_name: parallel_map_pads_shorter_lists_with_null,
+    render_root: t,
+    template_group: {
+        t(names, phones, salaries) ::= r#"<names, phones, salaries:{n, p, s | <n>@<p>: <s>}; separator=", ">"#
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom", "Sumana"] },
+        "phones": { vec!["x5001", "x5002"] },
+        "salaries": { vec!["big", "huge"] },
+    },
+    expected: "Ter@x5001: big, Tom@x5002: huge, Sumana@: ",
+}
+
+st_test! {
+    test_name: parallel_map_with_index,
+    render_root: t,
+    template_group: {
+        t(names, phones) ::= "<names, phones:{n, p | <i>. <n>:<p>\n}>"
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom"] },
+        "phones": { vec!["x5001", "x5002"] },
+    },
+    expected: "1. Ter:x5001\n2. Tom:x5002\n",
+}
+
+st_test! {
+    test_name: parallel_map_of_single_values,
+    render_root: t,
+    template_group: {
+        t(name, phone) ::= "<name, phone:{n, p | <n>:<p>}>"
+    },
+    attributes: {
+        "name": "Ter",
+        "phone": "x5001",
+    },
+    expected: "Ter:x5001",
+}
+
+st_test! {
+    test_name: parallel_map_then_map,
+    render_root: t,
+    template_group: {
+        t(names, phones) ::= "<names, phones:{n, p | <n>:<p>}:{x | [<x>]}>"
+    },
+    attributes: {
+        "names": { vec!["Ter", "Tom"] },
+        "phones": { vec!["x5001", "x5002"] },
+    },
+    expected: "[Ter:x5001][Tom:x5002]",
+}
+
+st_test! {
+    test_name: round_robin_map,
+    render_root: t,
+    template_group: {
+        t(rows) ::= r#"<rows:even(), odd(); separator=", ">"#
+        even(row) ::= "even <row>"
+        odd(row) ::= "odd <row>"
+    },
+    attributes: {
+        "rows": { vec!["a", "b", "c"] },
+    },
+    expected: "even a, odd b, even c",
+}
+
+st_test! {
+    test_name: round_robin_map_with_anonymous_templates,
+    render_root: t,
+    template_group: {
+        t(rows) ::= "<rows:{r | [<r>]}, {r | (<r>)}, {r | |<r>|}>"
+    },
+    attributes: {
+        "rows": { vec!["a", "b", "c", "d"] },
+    },
+    expected: "[a](b)|c|[d]",
+}
+
+st_test! {
+    test_name: round_robin_map_skips_nulls,
+    render_root: t,
+    template_group: {
+        t(rows) ::= "<rows:{r | [<r>]}, {r | (<r>)}>"
+    },
+    attributes: {
+        "rows": { vec![Some("a"), None, Some("b")] },
+    },
+    expected: "[a](b)",
+}
+
+st_test! {
+    test_name: round_robin_map_with_index,
+    render_root: t,
+    template_group: {
+        t(rows) ::= r#"<rows:even(), odd(); separator=", ">"#
+        even(row) ::= "<i>:<row>"
+        odd(row) ::= "<i0>:<row>"
+    },
+    attributes: {
+        "rows": { vec!["a", "b", "c"] },
+    },
+    expected: "1:a, 1:b, 3:c",
+}
+
+st_test! {
+    test_name: map_argument_is_not_round_robin,
+    render_root: t,
+    template_group: {
+        t(rows) ::= "<b(rows:{r | [<r>]}, c())>"
+        b(x, y) ::= "<x>/<y>"
+        c() ::= "c"
+    },
+    attributes: {
+        "rows": { vec!["a", "b"] },
+    },
+    expected: "[a][b]/c",
+}
@@ -6,6 +6,8 @@ mod tests;
 
 pub trait TemplateTestExt {
     fn add_expect(&mut self, name: impl Into<String>, value: impl Serialize) -> &mut Self;
+
+    fn render_expect(&self) -> String;
 }
 
 impl TemplateTestExt for Template {
@@ -13,6 +15,11 @@ impl TemplateTestExt for Template {
         self.add(name, value)
             .expect("unexpectedly failed to add attribute to template")
     }
+
+    fn render_expect(&self) -> String {
+        self.render()
+            .expect("unexpectedly failed to render template")
+    }
 }
 
 pub fn get_template(group: &Group, name: &'static str) -> Template {
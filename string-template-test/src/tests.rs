@@ -2,7 +2,18 @@ use super::TemplateTestExt;
 
 use serde_derive::Serialize;
 
-use string_template::{CompiledTemplate, Error, Group, Template};
+use std::cell::Cell;
+use std::fmt::{self, Write};
+use std::io;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use serde_json::Value as Json;
+
+use string_template::{
+    AttributeRenderer, CompiledTemplate, Context, Delimiters, Error, Group, Location, ModelAdaptor,
+    NoIndentWriter, Template, ValueKind,
+};
 
 fn parse_template(template: &'static str) -> Template {
     template
@@ -16,7 +27,14 @@ fn renders_hello_world() {
     let mut hello = parse_template("Hello, <name>!");
     println!("{:?}", hello);
     hello.add_expect("name", "World");
-    assert_eq!("Hello, World!", format!("{}", hello.render()));
+    assert_eq!("Hello, World!", format!("{}", hello.render_expect()));
+}
+
+#[test]
+fn renders_tabs_and_non_ascii_literal_text() {
+    let mut t = parse_template("a\tb <x>, héllo <x>");
+    t.add_expect("x", "wörld");
+    assert_eq!("a\tb wörld, héllo wörld", t.render_expect());
 }
 
 #[test]
@@ -24,14 +42,14 @@ fn renders_multiple_attributes() {
     let mut hello = parse_template("Hello, <title><name>!");
     hello.add_expect("name", "World");
     hello.add_expect("title", "Old ");
-    assert_eq!("Hello, Old World!", format!("{}", hello.render()));
+    assert_eq!("Hello, Old World!", format!("{}", hello.render_expect()));
 }
 
 #[test]
 fn renders_missing_attributes_as_empty_string() {
     let mut hello = parse_template("Hello, <title><name>!");
     hello.add_expect("name", "World");
-    assert_eq!("Hello, World!", format!("{}", hello.render()));
+    assert_eq!("Hello, World!", format!("{}", hello.render_expect()));
 }
 
 #[test]
@@ -41,7 +59,7 @@ fn renders_chained_attributes() {
         .add_expect("names", "Ter")
         .add_expect("names", "Tom")
         .add_expect("x", 1);
-    assert_eq!("1:TerTom!", template.render());
+    assert_eq!("1:TerTom!", template.render_expect());
 }
 
 #[test]
@@ -51,7 +69,7 @@ fn renders_multiple_chained_attributes() {
         .add_expect("names", "Ter")
         .add_expect("names", "Tom")
         .add_expect("names", 1);
-    assert_eq!("TerTom1!", template.render());
+    assert_eq!("TerTom1!", template.render_expect());
 }
 
 #[test]
@@ -63,7 +81,23 @@ fn renders_nested_attributes() {
     let mut hello = parse_template("Hello, <person.name>!");
     let john = Person { name: "John" };
     hello.add_expect("person", &john);
-    assert_eq!("Hello, John!", format!("{}", hello.render()));
+    assert_eq!("Hello, John!", format!("{}", hello.render_expect()));
+}
+
+#[test]
+fn navigates_json_paths() {
+    let json: Json = serde_json::from_str(r#"{"a": {"b": [1, 2], "c": "d"}}"#).unwrap();
+    let context = Context::new(json);
+    assert_eq!(
+        Context::new(Json::from(vec![1, 2])),
+        context.navigate(&["a", "b"])
+    );
+    assert_eq!(Context::null(), context.navigate(&["a", "x"]));
+    assert_eq!(Context::null(), context.navigate(&["x", "y"]));
+    assert_eq!(
+        Context::new(Json::from("d")),
+        context.navigate(&["a", "c", "e"])
+    );
 }
 
 #[test]
@@ -80,7 +114,7 @@ fn errors_setting_unknown_attribute() {
 fn renders_an_attribute_list_concatenated() {
     let mut hello = parse_template("Hello, <names>!");
     hello.add_expect("names", &["Jeff", "John", "Carl"]);
-    assert_eq!("Hello, JeffJohnCarl!", format!("{}", hello.render()));
+    assert_eq!("Hello, JeffJohnCarl!", format!("{}", hello.render_expect()));
 }
 
 fn parse_group(group: &'static str) -> Group {
@@ -104,7 +138,7 @@ a() ::= "FOO"
 "#,
     );
     let a = get_template(&group, "a");
-    assert_eq!("FOO", a.render());
+    assert_eq!("FOO", a.render_expect());
 }
 
 #[test]
@@ -116,7 +150,7 @@ a(x) ::= "FOO<x>"
     );
     let mut a = get_template(&group, "a");
     a.add_expect("x", "BAR");
-    assert_eq!("FOOBAR", a.render());
+    assert_eq!("FOOBAR", a.render_expect());
 }
 
 #[test]
@@ -128,7 +162,7 @@ b() ::= "BAR"
 "#,
     );
     let a = get_template(&group, "a");
-    assert_eq!("FOOBAR", a.render());
+    assert_eq!("FOOBAR", a.render_expect());
 }
 
 #[test]
@@ -139,5 +173,724 @@ a() ::= "FOO<b()>"
 "#,
     );
     let a = get_template(&group, "a");
-    assert_eq!("FOO", a.render());
+    assert_eq!("FOO", a.render_expect());
+}
+
+#[test]
+fn errors_parsing_unknown_option() {
+    assert!(r#"<name; fancy="yes">"#.parse::<CompiledTemplate>().is_err());
+}
+
+#[test]
+fn errors_parsing_option_missing_required_value() {
+    assert!("<names; separator>".parse::<CompiledTemplate>().is_err());
+}
+
+#[test]
+fn errors_passing_too_many_arguments() {
+    let group = parse_group(
+        r#"
+a() ::= "<b(\"x\", \"y\")>"
+b(x) ::= "<x>"
+"#,
+    );
+    let a = get_template(&group, "a");
+    assert_eq!(
+        Err(Error::ArgumentCountMismatch("b".into(), 1, 2)),
+        a.render()
+    );
+}
+
+#[test]
+fn errors_passing_too_few_arguments() {
+    let group = parse_group(
+        r#"
+a() ::= "<b()>"
+b(x) ::= "<x>"
+"#,
+    );
+    let a = get_template(&group, "a");
+    assert_eq!(
+        Err(Error::ArgumentCountMismatch("b".into(), 1, 0)),
+        a.render()
+    );
+}
+
+#[test]
+fn errors_passing_unknown_named_argument() {
+    let group = parse_group(
+        r#"
+a() ::= "<b(y=\"x\")>"
+b(x) ::= "<x>"
+"#,
+    );
+    let a = get_template(&group, "a");
+    assert_eq!(Err(Error::NoSuchAttribute("y".into())), a.render());
+}
+
+#[test]
+fn renders_include_without_dynamic_scoping() {
+    let group = parse_group(
+        r#"
+a(x) ::= "<b()>"
+b() ::= "[<x>]"
+"#,
+    );
+    group.set_dynamic_scoping(false);
+    let mut a = get_template(&group, "a");
+    a.add_expect("x", "FOO");
+    assert_eq!("[]", a.render_expect());
+}
+
+#[test]
+fn renders_pass_through_without_dynamic_scoping() {
+    let group = parse_group(
+        r#"
+a(x, y) ::= "<b(...)>"
+b(x) ::= "[<x><y>]"
+"#,
+    );
+    group.set_dynamic_scoping(false);
+    let mut a = get_template(&group, "a");
+    a.add_expect("x", "FOO").add_expect("y", "BAR");
+    assert_eq!("[FOO]", a.render_expect());
+}
+
+#[test]
+fn errors_setting_iteration_index_attributes() {
+    let mut template = parse_template("<names:{n | <i>. <n>}>");
+    assert_eq!(
+        Err(Error::ReservedAttribute("i".into())),
+        template.add("i", 1)
+    );
+    assert_eq!(
+        Err(Error::ReservedAttribute("i0".into())),
+        template.add("i0", 0)
+    );
+}
+
+#[test]
+fn errors_parsing_parallel_map_with_wrong_parameter_count() {
+    assert!("<a, b:{x | <x>}>".parse::<CompiledTemplate>().is_err());
+    assert!("<a, b:t()>".parse::<CompiledTemplate>().is_err());
+}
+
+struct PrecisionRenderer;
+
+impl AttributeRenderer for PrecisionRenderer {
+    fn render(&self, value: &Json, format: Option<&str>, locale: &str) -> String {
+        let number = value.as_f64().unwrap();
+        let precision = format.and_then(|format| format.parse().ok()).unwrap_or(2);
+        let rendered = format!("{:.*}", precision, number);
+        match locale {
+            "de-DE" => rendered.replace('.', ","),
+            _ => rendered,
+        }
+    }
+}
+
+struct ShoutingRenderer;
+
+impl AttributeRenderer for ShoutingRenderer {
+    fn render(&self, value: &Json, _format: Option<&str>, _locale: &str) -> String {
+        format!("{}!", value.as_str().unwrap().to_uppercase())
+    }
+}
+
+#[test]
+fn renders_numbers_with_registered_renderer() {
+    let group = parse_group(r#"t(x, y) ::= "<x> <y; format=\"3\">""#);
+    group.register_renderer(ValueKind::Number, PrecisionRenderer);
+    let mut t = get_template(&group, "t");
+    t.add_expect("x", 1.5);
+    t.add_expect("y", 2);
+    assert_eq!("1.50 2.000", t.render_expect());
+}
+
+#[test]
+fn passes_locale_to_registered_renderer() {
+    let group = parse_group(r#"t(x) ::= "<x>""#);
+    group.register_renderer(ValueKind::Number, PrecisionRenderer);
+    let mut t = get_template(&group, "t");
+    t.add_expect("x", 1.5);
+    assert_eq!(Ok("1,50".to_string()), t.render_with_locale("de-DE"));
+}
+
+#[test]
+fn renders_list_elements_with_registered_renderer() {
+    let group = parse_group(r#"t(xs) ::= "<xs; separator=\", \">""#);
+    group.register_renderer(ValueKind::Number, PrecisionRenderer);
+    let mut t = get_template(&group, "t");
+    t.add_expect("xs", vec![1, 2]);
+    assert_eq!("1.00, 2.00", t.render_expect());
+}
+
+#[test]
+fn renders_strings_with_registered_renderer() {
+    let group = parse_group(r#"t(x) ::= "<x; format=\"lower\">""#);
+    group.register_renderer(ValueKind::String, ShoutingRenderer);
+    let mut t = get_template(&group, "t");
+    t.add_expect("x", "hi");
+    assert_eq!("HI!", t.render_expect());
+}
+
+#[test]
+fn renders_string_and_list_literals_with_registered_renderer() {
+    let group = parse_group("t() ::= <<\nsay <\"hi\"> <[\"a\", \"b\"]; separator=\" \">\n>>");
+    group.register_renderer(ValueKind::String, ShoutingRenderer);
+    assert_eq!("say HI! A! B!", get_template(&group, "t").render_expect());
+}
+
+#[test]
+fn does_not_render_template_output_again() {
+    let group = parse_group(
+        r#"
+        t(x) ::= "<b(x)> <x:{y | <y>}>"
+        b(y) ::= "<y>"
+        "#,
+    );
+    group.register_renderer(ValueKind::String, ShoutingRenderer);
+    let mut t = get_template(&group, "t");
+    t.add_expect("x", "hi");
+    assert_eq!("HI! HI!", t.render_expect());
+}
+
+#[derive(Clone)]
+struct Person {
+    name: &'static str,
+    friends: Vec<Person>,
+    lookups: Rc<Cell<usize>>,
+}
+
+impl Person {
+    fn new(name: &'static str, friends: Vec<Person>) -> Person {
+        Person {
+            name,
+            friends,
+            lookups: Rc::new(Cell::new(0)),
+        }
+    }
+}
+
+impl ModelAdaptor for Person {
+    fn property(&self, name: &str) -> Option<Context> {
+        self.lookups.set(self.lookups.get() + 1);
+        match name {
+            "name" => Some(Context::new(Json::from(self.name))),
+            "friends" => {
+                let friends = self.friends.iter().cloned().map(Context::model);
+                Some(Context::list(friends.collect()))
+            }
+            "best" => self.friends.first().cloned().map(Context::model),
+            _ => None,
+        }
+    }
+
+    fn render(&self) -> String {
+        format!("Person({})", self.name)
+    }
+}
+
+#[test]
+fn renders_model_properties() {
+    let group = parse_group(r#"t(p) ::= "<p.name> [<p.age>]""#);
+    let mut t = get_template(&group, "t");
+    t.add_model("p", Person::new("Ter", vec![])).unwrap();
+    assert_eq!("Ter []", t.render_expect());
+}
+
+#[test]
+fn renders_nested_model_properties() {
+    let group = parse_group(r#"t(p) ::= "<p.best.name>""#);
+    let mut t = get_template(&group, "t");
+    let tom = Person::new("Tom", vec![]);
+    t.add_model("p", Person::new("Ter", vec![tom])).unwrap();
+    assert_eq!("Tom", t.render_expect());
+}
+
+#[test]
+fn renders_whole_model() {
+    let group = parse_group(r#"t(p) ::= "<p>""#);
+    let mut t = get_template(&group, "t");
+    t.add_model("p", Person::new("Ter", vec![])).unwrap();
+    assert_eq!("Person(Ter)", t.render_expect());
+}
+
+#[test]
+fn maps_over_models() {
+    let group = parse_group(r#"t(p) ::= "<p.friends:{f | <f.name>}; separator=\", \">""#);
+    let mut t = get_template(&group, "t");
+    let friends = vec![Person::new("Tom", vec![]), Person::new("Sumana", vec![])];
+    t.add_model("p", Person::new("Ter", friends)).unwrap();
+    assert_eq!("Tom, Sumana", t.render_expect());
+}
+
+#[test]
+fn maps_over_models_added_one_at_a_time() {
+    let group = parse_group(r#"t(ps) ::= "<ps:{p | <p.name>}; separator=\", \">""#);
+    let mut t = get_template(&group, "t");
+    t.add_model("ps", Person::new("Ter", vec![])).unwrap();
+    t.add_model("ps", Person::new("Tom", vec![])).unwrap();
+    assert_eq!("Ter, Tom", t.render_expect());
+}
+
+#[test]
+fn looks_up_model_properties_lazily() {
+    let group = parse_group(r#"t(p) ::= "<if(p)><p.name><endif>""#);
+    let mut t = get_template(&group, "t");
+    let person = Person::new("Ter", vec![Person::new("Tom", vec![])]);
+    let lookups = Rc::clone(&person.lookups);
+    t.add_model("p", person).unwrap();
+    assert_eq!("Ter", t.render_expect());
+    assert_eq!(1, lookups.get());
+}
+
+#[test]
+fn errors_adding_unknown_model() {
+    let group = parse_group(r#"t() ::= "hi""#);
+    let mut t = get_template(&group, "t");
+    assert_eq!(
+        Err(Error::NoSuchAttribute("p".into())),
+        t.add_model("p", Person::new("Ter", vec![]))
+    );
+}
+
+#[test]
+fn writes_to_io_writer() {
+    let group = parse_group(
+        r#"t(names) ::= "<names:{n | [<n>]}>, <u()>!"
+u() ::= "done""#,
+    );
+    let mut t = get_template(&group, "t");
+    t.add_expect("names", vec!["a", "b"]);
+    let mut out = Vec::new();
+    t.write_to(&mut out).unwrap();
+    assert_eq!("[a][b], done!", String::from_utf8(out).unwrap());
+}
+
+#[test]
+fn writes_to_io_writer_with_interpreter_options() {
+    let group = parse_group(r#"t(values) ::= "<values; wrap, separator=\",\">""#);
+    group.register_renderer(ValueKind::Number, PrecisionRenderer);
+    let mut t = get_template(&group, "t");
+    t.add_expect("values", vec![1.5, 2.25, 3.0]);
+    let interpreter = t.interpreter().with_line_width(10).with_locale("de-DE");
+    let mut out = Vec::new();
+    t.write_with(&interpreter, &mut out).unwrap();
+    assert_eq!("1,50,2,25,\n3,00", String::from_utf8(out).unwrap());
+    assert_eq!(
+        Ok("1,50,2,25,\n3,00".to_string()),
+        t.render_with(&interpreter)
+    );
+}
+
+#[test]
+fn displays_rendered_template() {
+    let mut t = parse_template("Hello, <name>!");
+    t.add_expect("name", "World");
+    assert_eq!("Hello, World!", t.to_string());
+    assert_eq!("<Hello, World!>", format!("<{}>", t));
+}
+
+#[test]
+fn fails_to_display_when_rendering_fails() {
+    let group = parse_group(
+        r#"a() ::= "FOO<b()>"
+b(x) ::= "<x>""#,
+    );
+    let a = get_template(&group, "a");
+    let mut out = String::new();
+    assert_eq!(Err(fmt::Error), write!(out, "{}", a));
+    let error = Error::ArgumentCountMismatch("b".into(), 1, 0);
+    assert_eq!(Err(error), a.render());
+}
+
+struct BrokenPipe;
+
+impl io::Write for BrokenPipe {
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::ErrorKind::BrokenPipe.into())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn propagates_io_errors() {
+    let t = parse_template("Hello!");
+    assert_eq!(
+        Err(Error::Io(io::ErrorKind::BrokenPipe.into())),
+        t.write_to(&mut BrokenPipe)
+    );
+}
+
+#[test]
+fn renders_without_indentation() {
+    let group = parse_group(
+        r#"t() ::= "begin\n  <u()>\nend"
+u() ::= "kewl\ndaddy""#,
+    );
+    let t = get_template(&group, "t");
+    assert_eq!("begin\n  kewl\n  daddy\nend", t.render_expect());
+    assert_eq!(
+        "begin\n  kewl\ndaddy\nend",
+        t.render_with_writer(NoIndentWriter::boxed).unwrap()
+    );
+}
+
+#[test]
+fn wraps_lists_at_line_width() {
+    let group = parse_group(r#"t(values) ::= "int[] a = { <values; wrap, separator=\",\"> };""#);
+    let mut t = get_template(&group, "t");
+    t.add_expect(
+        "values",
+        vec![3, 9, 20, 2, 1, 4, 6, 32, 5, 6, 77, 888, 1, 6, 32, 5],
+    );
+    assert_eq!(
+        "int[] a = { 3,9,20,2,1,4,\n6,32,5,6,77,888,1,6,32,5 };",
+        t.render_with_line_width(25).unwrap()
+    );
+}
+
+#[test]
+fn wraps_lists_to_anchor() {
+    let group =
+        parse_group(r#"t(values) ::= "int[] a = { <values; anchor, wrap, separator=\",\"> };""#);
+    let mut t = get_template(&group, "t");
+    t.add_expect("values", vec![3, 9, 20, 2, 1, 4, 6, 32, 5, 6, 77, 888, 1]);
+    assert_eq!(
+        "int[] a = { 3,9,20,2,1,4,\n            6,32,5,6,77,\n            888,1 };",
+        t.render_with_line_width(25).unwrap()
+    );
+}
+
+#[test]
+fn wraps_before_elements_that_would_pass_line_width() {
+    let group = parse_group(r#"t(x) ::= "<x; wrap, separator=\",\">""#);
+    let mut t = get_template(&group, "t");
+    t.add_expect("x", vec!["aaaaaaaaaa", "bbbbbbbbbb", "cccccccccc"]);
+    assert_eq!(
+        "aaaaaaaaaa,\nbbbbbbbbbb,\ncccccccccc",
+        t.render_with_line_width(15).unwrap()
+    );
+}
+
+#[test]
+fn wraps_mapped_templates_with_indentation() {
+    let group = parse_group(
+        r#"t(values) ::= "call(\n  <values:{v | [<v>]}; wrap=\"\\n\", separator=\",\">\n)""#,
+    );
+    let mut t = get_template(&group, "t");
+    t.add_expect("values", vec!["a", "b", "c", "d", "e", "f"]);
+    assert_eq!(
+        "call(\n  [a],[b],[c],\n  [d],[e],[f]\n)",
+        t.render_with_line_width(14).unwrap()
+    );
+}
+
+#[test]
+fn does_not_wrap_without_line_width() {
+    let group = parse_group(r#"t(values) ::= "<values; wrap, separator=\",\">""#);
+    let mut t = get_template(&group, "t");
+    t.add_expect("values", vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    assert_eq!("1,2,3,4,5,6,7,8,9", t.render_expect());
+    assert_eq!(
+        "1,2,3,4,5,6,7,8,9",
+        t.render_with_writer(NoIndentWriter::boxed).unwrap()
+    );
+}
+
+fn fixture(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join(path)
+}
+
+#[test]
+fn loads_group_file() {
+    let group = Group::from_file(fixture("group.stg")).unwrap();
+    let mut t = get_template(&group, "greet_all");
+    t.add_expect("names", vec!["Ter", "Tom"]);
+    assert_eq!("Hello, Ter! Hello, Tom!", t.render_expect());
+}
+
+#[test]
+fn errors_loading_missing_group_file() {
+    let path = fixture("missing.stg");
+    let location = Location { path, line: None };
+    let error = Error::Io(io::ErrorKind::NotFound.into());
+    assert_eq!(
+        Err(Error::Load(location, Box::new(error))),
+        Group::from_file(fixture("missing.stg"))
+    );
+}
+
+#[test]
+fn loads_templates_from_dir() {
+    let group = Group::from_dir(fixture("dir")).unwrap();
+    let mut t = get_template(&group, "t");
+    t.add_expect("names", vec!["Ter", "Tom"]);
+    assert_eq!("names:\n  [Ter]\n  [Tom]", t.render_expect());
+    assert_eq!("[y]", get_template(&group, "uses_sub").render_expect());
+    assert!(group.get("/sub/item").is_some());
+    assert!(group.get("missing").is_none());
+}
+
+#[test]
+fn errors_loading_invalid_template_from_dir() {
+    let group = Group::from_dir(fixture("dir")).unwrap();
+    match group.load("broken") {
+        Err(Error::Load(location, _)) => {
+            assert_eq!(fixture("dir/broken.st"), location.path);
+            assert_eq!(Some(3), location.line);
+        }
+        result => panic!("unexpectedly loaded invalid template: {:?}", result),
+    }
+}
+
+#[test]
+fn errors_loading_group_from_missing_dir() {
+    assert_eq!(
+        Err(Error::Io(io::ErrorKind::NotFound.into())),
+        Group::from_dir(fixture("missing"))
+    );
+}
+
+#[test]
+fn looks_up_templates_in_imported_groups() {
+    let base = parse_group(
+        r#"a() ::= "base a"
+b() ::= "base b""#,
+    );
+    let dialect = parse_group(r#"b() ::= "dialect b""#);
+    dialect.import(&base).unwrap();
+    assert_eq!("base a", get_template(&dialect, "a").render_expect());
+    assert_eq!("dialect b", get_template(&dialect, "b").render_expect());
+    assert!(dialect.get("c").is_none());
+}
+
+#[test]
+fn imported_templates_include_overriding_templates() {
+    let base = parse_group(
+        r#"a() ::= "[<b()>]"
+b() ::= "base b""#,
+    );
+    let dialect = parse_group(r#"b() ::= "dialect b""#);
+    dialect.import(&base).unwrap();
+    assert_eq!("[dialect b]", get_template(&dialect, "a").render_expect());
+    assert_eq!("[base b]", get_template(&base, "a").render_expect());
+}
+
+#[test]
+fn calls_overridden_templates_with_super() {
+    let base = parse_group(r#"a(x) ::= "base <x>""#);
+    let middle = parse_group(r#"a(x) ::= "middle (<super.a(x)>)""#);
+    let dialect = parse_group(r#"a(x) ::= "dialect (<super.a(x)>)""#);
+    middle.import(&base).unwrap();
+    dialect.import(&middle).unwrap();
+    let mut t = get_template(&dialect, "a");
+    t.add_expect("x", "y");
+    assert_eq!("dialect (middle (base y))", t.render_expect());
+}
+
+#[test]
+fn looks_up_dictionaries_in_imported_groups() {
+    let base = parse_group(r#"kinds ::= ["a": "apple"]"#);
+    let dialect = parse_group(r#"t() ::= "<kinds.a>""#);
+    dialect.import(&base).unwrap();
+    assert_eq!("apple", get_template(&dialect, "t").render_expect());
+}
+
+#[test]
+fn imports_groups_in_group_files() {
+    let group = Group::from_file(fixture("dialect.stg")).unwrap();
+    let mut t = get_template(&group, "method");
+    t.add_expect("name", "f");
+    assert_eq!("pub fn f() { todo!() // generated }", t.render_expect());
+}
+
+#[test]
+fn errors_loading_circular_imports() {
+    let mut error = Group::from_file(fixture("cycle_a.stg")).unwrap_err();
+    while let Error::Load(_, cause) = error {
+        error = *cause;
+    }
+    assert_eq!(Error::CircularImport, error);
+}
+
+#[test]
+fn errors_importing_groups_circularly() {
+    let a = parse_group(r#"a() ::= "a""#);
+    let b = parse_group(r#"b() ::= "b""#);
+    let c = parse_group(r#"c() ::= "c""#);
+    a.import(&b).unwrap();
+    b.import(&c).unwrap();
+    assert_eq!(Err(Error::CircularImport), c.import(&a));
+    assert_eq!(Err(Error::CircularImport), a.import(&a));
+    assert!(a.get("d").is_none());
+    assert!(!format!("{:?}", a).is_empty());
+    assert_eq!(a, a);
+}
+
+#[test]
+fn strips_newlines_around_big_strings() {
+    let group = parse_group("t(x) ::= <<\n  [<x>]\n  done\n>>");
+    let mut t = get_template(&group, "t");
+    t.add_expect("x", "y");
+    assert_eq!("  [y]\n  done", t.render_expect());
+}
+
+#[test]
+fn ignores_newlines_and_indentation_in_percent_bodies() {
+    let group = parse_group(
+        "t(x) ::= <%\n    <if(x)>\n        yes\n    <else>\n        no\n    <endif>\n%>",
+    );
+    let mut t = get_template(&group, "t");
+    t.add_expect("x", true);
+    assert_eq!("yes", t.render_expect());
+}
+
+#[test]
+fn parses_tabs_in_template_bodies() {
+    let group = parse_group("t(x) ::= \"a\\tb <x>\"\nu() ::= <<\n\tindented\n>>");
+    let mut t = get_template(&group, "t");
+    t.add_expect("x", "c");
+    assert_eq!("a\tb c", t.render_expect());
+    assert_eq!("\tindented", get_template(&group, "u").render_expect());
+}
+
+#[test]
+fn unescapes_quoted_bodies() {
+    let group = parse_group(r#"t() ::= "say \"hi\"\n\\done""#);
+    assert_eq!(
+        "say \"hi\"\n\\done",
+        get_template(&group, "t").render_expect()
+    );
+}
+
+#[test]
+fn parses_anonymous_template_defaults_with_or_without_quotes() {
+    let group = parse_group(
+        r#"t(x, body={"<x>!"}) ::= "[<body>]"
+u(x, body={ <x>"! }) ::= "[<body>]"
+"#,
+    );
+    let mut t = get_template(&group, "t");
+    t.add_expect("x", "Ter");
+    assert_eq!("[Ter!]", t.render_expect());
+    let mut u = get_template(&group, "u");
+    u.add_expect("x", "Ter");
+    assert_eq!("[ Ter\"! ]", u.render_expect());
+}
+
+#[test]
+fn parses_group_files_with_comments() {
+    let group = parse_group(
+        r#"/* Greets
+ * everyone. */
+greet(name, greeting="Hello") ::= "<greeting>, <name>!"
+// Plural forms of words.
+plurals ::= [
+    "child": "children",
+    default: key,
+]
+t() ::= "<greet(plurals.child)> <greet(plurals.dog)>"
+"#,
+    );
+    let t = get_template(&group, "t");
+    assert_eq!("Hello, children! Hello, dog!", t.render_expect());
+}
+
+#[test]
+fn errors_loading_invalid_group_file_on_line() {
+    match Group::from_file(fixture("broken.stg")) {
+        Err(Error::Load(location, _)) => {
+            assert_eq!(fixture("broken.stg"), location.path);
+            assert_eq!(Some(5), location.line);
+        }
+        result => panic!("unexpectedly loaded invalid group: {:?}", result),
+    }
+}
+
+#[test]
+fn keeps_special_characters_in_quoted_bodies() {
+    let group = parse_group(r#"t() ::= "a<\n>b\nc\<""#);
+    assert_eq!("a\nb\nc<", get_template(&group, "t").render_expect());
+}
+
+#[test]
+fn adds_templates_with_group_delimiters() {
+    let group = Group::new();
+    group.set_delimiters(Delimiters::new('$', '$'));
+    group.add_template("t", "<$x$>").unwrap();
+    let mut t = get_template(&group, "t");
+    t.add_expect("x", "y");
+    assert_eq!("<y>", t.render_expect());
+}
+
+#[test]
+fn loads_templates_from_dir_with_group_delimiters() {
+    let group = Group::from_dir(fixture("dollar")).unwrap();
+    group.set_delimiters(Delimiters::new('$', '$'));
+    let mut t = get_template(&group, "page");
+    t.add_expect("title", "Home");
+    assert_eq!("<h1>Home</h1>", t.render_expect());
+}
+
+#[test]
+fn parses_delimiters_of_group_files() {
+    let group = parse_group("delimiters \"$\", \"$\"\nt(x) ::= <<\n<$x$>\n>>");
+    assert_eq!(Delimiters::new('$', '$'), group.delimiters());
+    let mut t = get_template(&group, "t");
+    t.add_expect("x", "y");
+    assert_eq!("<y>", t.render_expect());
+}
+
+#[test]
+fn unescapes_quoted_bodies_with_group_delimiters() {
+    let group = parse_group(
+        r#"delimiters "$", "$"
+t(x) ::= "a < b\n\$ $x$$\n$""#,
+    );
+    let mut t = get_template(&group, "t");
+    t.add_expect("x", "y");
+    assert_eq!("a < b\n$ y\n", t.render_expect());
+}
+
+#[test]
+fn errors_parsing_delimiters_longer_than_a_character() {
+    assert!("delimiters \"<<\", \">>\"".parse::<Group>().is_err());
+}
+
+#[test]
+fn overrides_regions_in_importing_groups() {
+    let base = parse_group(r#"t() ::= "a<@r>base<@end>b<@s()>""#);
+    let dialect = parse_group(
+        r#"@t.r() ::= "dialect"
+@t.s() ::= "!""#,
+    );
+    dialect.import(&base).unwrap();
+    assert_eq!("adialectb!", get_template(&dialect, "t").render_expect());
+    assert_eq!("abaseb", get_template(&base, "t").render_expect());
+}
+
+#[test]
+fn overrides_regions_with_most_derived_group() {
+    let base = parse_group(r#"t() ::= "<@r()>""#);
+    let middle = parse_group(r#"@t.r() ::= "middle""#);
+    let dialect = parse_group(r#"@t.r() ::= "dialect""#);
+    middle.import(&base).unwrap();
+    dialect.import(&middle).unwrap();
+    assert_eq!("middle", get_template(&middle, "t").render_expect());
+    assert_eq!("dialect", get_template(&dialect, "t").render_expect());
+}
+
+#[test]
+fn names_regions_of_added_templates() {
+    let group = Group::new();
+    group.add_template("t", "<@r>default<@end>").unwrap();
+    group.add_template("@t.r", "added").unwrap();
+    assert_eq!("added", get_template(&group, "t").render_expect());
 }
@@ -1,87 +1,251 @@
+use std::rc::Rc;
 use std::{fmt, mem};
 
-use serde::Serialize;
+use serde::ser::{Serialize, SerializeSeq, Serializer};
 use serde_json::value::{to_value, Value as Json};
 
 use crate::Error;
 
+/// Exposes the properties of a Rust value to templates, so that
+/// `<obj.prop>` can look them up as they're needed instead of the
+/// whole value being converted to JSON up front.
+///
+/// JSON values are adapted by looking up the fields of objects, which
+/// is what values added with `Template::add` use.
+pub trait ModelAdaptor {
+    /// The value of the property `name`, or `None` if there is no such
+    /// property.
+    fn property(&self, name: &str) -> Option<Context>;
+
+    /// The text of the value when it's rendered as a whole.
+    fn render(&self) -> String {
+        "[object]".to_owned()
+    }
+}
+
+impl ModelAdaptor for Json {
+    fn property(&self, name: &str) -> Option<Context> {
+        self.get(name).cloned().map(Context::new)
+    }
+
+    fn render(&self) -> String {
+        JsonRender::render(self)
+    }
+}
+
+#[derive(Clone)]
+enum Data {
+    Json(Json),
+    /// A list holding models, which can't be a JSON array.
+    List(Vec<Context>),
+    Model(Rc<dyn ModelAdaptor>),
+}
+
 /// The context wraps the attribute values attached to a template.
 ///
-#[derive(Debug, Default, Clone, PartialEq)]
+#[derive(Clone)]
 pub struct Context {
-    data: Json,
+    data: Data,
 }
 
 impl Context {
+    /// The null context, for borrowing where there's no value.
+    pub(crate) const NULL: Context = Context {
+        data: Data::Json(Json::Null),
+    };
+
     /// Create a context with null data
     pub fn null() -> Context {
-        Context { data: Json::Null }
+        Context::new(Json::Null)
     }
 
     pub fn new(data: Json) -> Context {
-        Context { data }
+        Context {
+            data: Data::Json(data),
+        }
     }
 
     /// Create a context with given data
     pub fn wraps<T: Serialize>(e: T) -> Result<Context, Error> {
-        to_value(e)
-            .map_err(Error::from)
-            .map(|d| Context { data: d })
+        to_value(e).map_err(Error::from).map(Context::new)
     }
 
-    pub fn array() -> Context {
+    /// Create a context for a value whose properties are read through
+    /// its adaptor.
+    pub fn model(model: impl ModelAdaptor + 'static) -> Context {
         Context {
-            data: Json::Array(vec![]),
+            data: Data::Model(Rc::new(model)),
+        }
+    }
+
+    pub fn array() -> Context {
+        Context::new(Json::Array(vec![]))
+    }
+
+    /// Create a context for a list of values, which may be models.
+    pub fn list(items: Vec<Context>) -> Context {
+        if items.iter().all(|item| item.as_json().is_some()) {
+            let items = items.into_iter().map(Context::into_json).collect();
+            Context::new(Json::Array(items))
+        } else {
+            Context {
+                data: Data::List(items),
+            }
         }
     }
 
     pub fn concat(&mut self, new_value: Context) {
-        match self.data {
-            Json::Null => {
-                mem::replace(self, new_value);
+        match (&mut self.data, new_value.data) {
+            (Data::Json(Json::Null), data) => self.data = data,
+            (Data::Json(Json::Array(list)), Data::Json(new_value)) => list.push(new_value),
+            (Data::List(list), data) => list.push(Context { data }),
+            (node, data) => {
+                let previous = Context {
+                    data: mem::replace(node, Data::Json(Json::Null)),
+                };
+                let mut items = previous.into_list().unwrap_or_else(|item| vec![item]);
+                items.push(Context { data });
+                *self = Context::list(items);
             }
+        }
+    }
 
-            Json::Array(ref mut list) => list.push(new_value.into_inner()),
+    /// The data as JSON, unless it's a model or holds models.
+    pub fn as_json(&self) -> Option<&Json> {
+        match &self.data {
+            Data::Json(json) => Some(json),
+            Data::List(_) | Data::Model(_) => None,
+        }
+    }
 
-            ref mut node => {
-                let previous = node.take();
-                let new = Json::Array(vec![previous, new_value.into_inner()]);
-                mem::replace(node, new);
-            }
+    /// Convert the data to JSON. Models are converted to the text they
+    /// render as.
+    pub fn into_json(self) -> Json {
+        match self.data {
+            Data::Json(json) => json,
+            Data::List(items) => Json::Array(items.into_iter().map(Context::into_json).collect()),
+            Data::Model(model) => Json::String(model.render()),
+        }
+    }
+
+    /// The items of the data if it's a list, or else the data itself.
+    pub fn into_list(self) -> Result<Vec<Context>, Context> {
+        match self.data {
+            Data::Json(Json::Array(list)) => Ok(list.into_iter().map(Context::new).collect()),
+            Data::List(items) => Ok(items),
+            data => Err(Context { data }),
         }
     }
 
-    pub fn borrow(&self) -> &Json {
-        &self.data
+    pub fn is_null(&self) -> bool {
+        match &self.data {
+            Data::Json(json) => json.is_null(),
+            Data::List(_) | Data::Model(_) => false,
+        }
     }
 
-    pub fn into_inner(self) -> Json {
-        self.data
+    /// Test whether the data counts as true in a conditional.
+    ///
+    /// Null, `false` and empty lists or objects are false, anything
+    /// else (including the empty string and models) is true.
+    pub fn is_truthy(&self) -> bool {
+        match &self.data {
+            Data::Json(Json::Null) => false,
+            Data::Json(Json::Bool(b)) => *b,
+            Data::Json(Json::Array(list)) => !list.is_empty(),
+            Data::Json(Json::Object(map)) => !map.is_empty(),
+            Data::Json(Json::Number(_)) | Data::Json(Json::String(_)) => true,
+            Data::List(items) => !items.is_empty(),
+            Data::Model(_) => true,
+        }
     }
 }
 
 impl Context {
+    /// The value at `path` within the data, looking up each property in
+    /// turn until one isn't an object or model.
+    ///
+    /// JSON is navigated by reference, so only the value found is
+    /// cloned.
     pub fn navigate(&self, path: &[&str]) -> Context {
-        let mut node = &self.data;
-        for segment in path {
-            match node {
-                Json::Object(map) => node = map.get::<str>(*segment).unwrap_or(&Json::Null),
-                _ => break,
+        match (&self.data, path.split_first()) {
+            (Data::Json(json), _) => Context::new(navigate_json(json, path).clone()),
+            (Data::Model(model), Some((segment, rest))) => {
+                model.property(segment).unwrap_or_default().navigate(rest)
             }
+            _ => self.clone(),
         }
+    }
+}
 
-        Context::wraps(node).unwrap()
+fn navigate_json<'a>(mut json: &'a Json, path: &[&str]) -> &'a Json {
+    for segment in path {
+        json = match json {
+            Json::Object(map) => map.get(*segment).unwrap_or(&Json::Null),
+            _ => break,
+        };
+    }
+    json
+}
+
+impl Default for Context {
+    fn default() -> Context {
+        Context::null()
+    }
+}
+
+impl fmt::Debug for Context {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.data {
+            Data::Json(json) => f.debug_struct("Context").field("data", json).finish(),
+            Data::List(items) => f.debug_struct("Context").field("data", items).finish(),
+            Data::Model(_) => f.debug_struct("Context").field("data", &"[model]").finish(),
+        }
+    }
+}
+
+impl PartialEq for Context {
+    fn eq(&self, other: &Context) -> bool {
+        match (&self.data, &other.data) {
+            (Data::Json(json1), Data::Json(json2)) => json1 == json2,
+            (Data::List(items1), Data::List(items2)) => items1 == items2,
+            (Data::Model(model1), Data::Model(model2)) => Rc::ptr_eq(model1, model2),
+            _ => false,
+        }
+    }
+}
+
+impl Serialize for Context {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match &self.data {
+            Data::Json(json) => json.serialize(serializer),
+            Data::List(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            Data::Model(model) => serializer.serialize_str(&model.render()),
+        }
     }
 }
 
 impl fmt::Display for Context {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.data.render())
+        match &self.data {
+            Data::Json(json) => write!(f, "{}", JsonRender::render(json)),
+            Data::List(items) => items.iter().try_for_each(|item| write!(f, "{}", item)),
+            Data::Model(model) => write!(f, "{}", model.render()),
+        }
     }
 }
 
 /// Render Json data with default format
-trait JsonRender {
+pub(crate) trait JsonRender {
     fn render(&self) -> String;
 }
 
@@ -95,7 +259,7 @@ impl JsonRender for Json {
             Json::Array(a) => {
                 let mut buf = String::new();
                 for i in a.iter() {
-                    buf.push_str(i.render().as_ref());
+                    buf.push_str(JsonRender::render(i).as_ref());
                 }
                 buf
             }
@@ -1,3 +1,6 @@
+use std::path::PathBuf;
+use std::{fmt, io};
+
 use failure::Fail;
 
 use serde_json::error::Error as SerdeError;
@@ -14,6 +17,46 @@ pub enum Error {
 
     #[fail(display = "no such attribute: {}", _0)]
     NoSuchAttribute(String),
+
+    #[fail(
+        display = "template {} takes {} argument(s) but {} were passed",
+        _0, _1, _2
+    )]
+    ArgumentCountMismatch(String, usize, usize),
+
+    #[fail(display = "attribute name is reserved: {}", _0)]
+    ReservedAttribute(String),
+
+    #[fail(display = "{}", _0)]
+    Io(io::Error),
+
+    #[fail(display = "{}", _0)]
+    Fmt(fmt::Error),
+
+    #[fail(display = "{}: {}", _0, _1)]
+    Load(Location, Box<Error>),
+
+    /// A group imports itself, either directly or through the groups it
+    /// imports.
+    #[fail(display = "circular import")]
+    CircularImport,
+}
+
+/// The file, and the line in it if it's known, that a group or
+/// template failed to load from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}", self.path.display(), line),
+            None => write!(f, "{}", self.path.display()),
+        }
+    }
 }
 
 impl PartialEq for Error {
@@ -21,8 +64,18 @@ impl PartialEq for Error {
         use self::Error::*;
 
         match (self, other) {
-            (Parse(_), Parse(_)) | (Serde(_), Serde(_)) => true,
+            (Parse(_), Parse(_)) | (Serde(_), Serde(_)) | (Fmt(_), Fmt(_)) => true,
+            (CircularImport, CircularImport) => true,
+            (Io(error1), Io(error2)) => error1.kind() == error2.kind(),
             (NoSuchAttribute(name1), NoSuchAttribute(name2)) if name1 == name2 => true,
+            (ReservedAttribute(name1), ReservedAttribute(name2)) if name1 == name2 => true,
+            (Load(location1, error1), Load(location2, error2)) => {
+                location1 == location2 && error1 == error2
+            }
+            (
+                ArgumentCountMismatch(name1, expected1, count1),
+                ArgumentCountMismatch(name2, expected2, count2),
+            ) => name1 == name2 && expected1 == expected2 && count1 == count2,
             _ => false,
         }
     }
@@ -34,6 +87,18 @@ impl From<ParseError> for Error {
     }
 }
 
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error::Io(error)
+    }
+}
+
+impl From<fmt::Error> for Error {
+    fn from(error: fmt::Error) -> Error {
+        Error::Fmt(error)
+    }
+}
+
 impl From<SerdeError> for Error {
     fn from(error: SerdeError) -> Error {
         Error::Serde(error)
@@ -1,34 +1,770 @@
-use crate::{Attributes, CompiledTemplate, Expr, Group, Template};
+use std::borrow::Cow;
+use std::fmt;
+
+use serde_json::Value as Json;
+
+use crate::context::JsonRender;
+use crate::renderer::{AttributeRenderer, StringRenderer, ValueKind, DEFAULT_LOCALE};
+use crate::writer::{AutoIndentWriter, NewWriter, StWriter};
+use crate::{
+    Arguments, Attributes, CompiledTemplate, Context, Dictionary, Error, Expr, ExprOption,
+    FormalArguments, Function, Group,
+};
+
+/// The attribute name bound to the element being iterated over when
+/// a template doesn't name its own parameters.
+const IMPLICIT_ARGUMENT: &str = "it";
+
+/// The attributes bound to the 1-based and 0-based index of the
+/// element being iterated over.
+pub(crate) const INDEX: &str = "i";
+pub(crate) const INDEX0: &str = "i0";
+
+/// The prefix of includes calling the template that the including
+/// template's group overrides, from the groups it imports.
+const SUPER: &str = "super.";
+
+/// An element of a list that a template is being applied to, or
+/// the elements at the same index of lists iterated in parallel.
+struct Element {
+    values: Vec<Context>,
+    index: usize,
+}
+
+impl Element {
+    fn new(value: Context, index: usize) -> Element {
+        Element {
+            values: vec![value],
+            index,
+        }
+    }
+
+    fn bind_index(&self, attributes: &mut Attributes) {
+        attributes.insert(INDEX, Context::new(Json::from(self.index + 1)));
+        attributes.insert(INDEX0, Context::new(Json::from(self.index)));
+    }
+}
+
+/// The attributes visible while rendering a template, chained to
+/// the scopes of the templates enclosing it.
+struct Scope<'a> {
+    formal_arguments: Option<&'a FormalArguments>,
+    attributes: &'a Attributes,
+    /// The group defining the template, which `super.` includes look
+    /// up templates in the imports of.
+    native_group: &'a Group,
+    parent: Option<&'a Scope<'a>>,
+}
+
+impl<'a> Scope<'a> {
+    fn new(
+        template: &'a CompiledTemplate,
+        attributes: &'a Attributes,
+        native_group: &'a Group,
+        parent: Option<&'a Scope<'a>>,
+    ) -> Scope<'a> {
+        Scope {
+            formal_arguments: template.formal_arguments.as_ref(),
+            attributes,
+            native_group,
+            parent,
+        }
+    }
+
+    /// The scope of an anonymous template nested inside this one.
+    fn nested(&'a self, attributes: &'a Attributes) -> Scope<'a> {
+        Scope {
+            formal_arguments: None,
+            attributes,
+            native_group: self.native_group,
+            parent: Some(self),
+        }
+    }
+
+    /// Whether `name` belongs to this scope, shadowing any attribute
+    /// of the same name in the enclosing scopes even when it's unset.
+    fn declares(&self, name: &str) -> bool {
+        let is_formal_argument = self
+            .formal_arguments
+            .is_some_and(|formal_arguments| formal_arguments.contains_key(name));
+        is_formal_argument || self.attributes.contains(name)
+    }
+
+    /// Whether `name` belongs to this scope or an enclosing one.
+    fn resolves(&self, name: &str) -> bool {
+        self.declares(name) || self.parent.is_some_and(|parent| parent.resolves(name))
+    }
+
+    fn get(&self, name: &str) -> &'a Context {
+        match self.parent {
+            Some(parent) if !self.declares(name) => parent.get(name),
+            _ => self.attributes.get(name),
+        }
+    }
+}
+
+/// The rendered values of the options given to an expression.
+#[derive(Debug, Default)]
+struct Options {
+    format: Option<String>,
+    null: Option<String>,
+    separator: Option<String>,
+    wrap: Option<String>,
+    anchor: bool,
+    /// Whether the value is template output, which has already been
+    /// rendered.
+    rendered: bool,
+}
+
+/// Whether the value of `expr` is the output of templates, which
+/// have already been rendered and so shouldn't be formatted again.
+fn is_template_output(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Include(..)
+            | Expr::IndirectInclude(..)
+            | Expr::Region(..)
+            | Expr::SubTemplate(..)
+            | Expr::Map(..)
+            | Expr::ParallelMap(..)
+            | Expr::RoundRobinMap(..)
+    )
+}
+
+/// Apply one of the built-in functions. The functions on lists treat
+/// any other value that isn't null as a list of that one value.
+fn call(function: Function, value: Context) -> Context {
+    match function {
+        Function::Length => {
+            let length = match value.into_list() {
+                Ok(list) => list.len(),
+                Err(value) if value.is_null() => 0,
+                Err(_) => 1,
+            };
+            return Context::new(Json::from(length));
+        }
+        Function::Trim => {
+            return match value.as_json() {
+                Some(Json::String(string)) => Context::new(Json::from(string.trim())),
+                _ => value,
+            };
+        }
+        Function::Strlen => return Context::new(Json::from(value.to_string().chars().count())),
+        _ => {}
+    }
+    match (function, value.into_list()) {
+        (Function::First, Ok(list)) => list.into_iter().next().unwrap_or_default(),
+        (Function::Last, Ok(mut list)) => list.pop().unwrap_or_default(),
+        (Function::Rest, Ok(mut list)) if list.len() > 1 => {
+            list.remove(0);
+            Context::list(list)
+        }
+        (Function::Trunc, Ok(mut list)) if list.len() > 1 => {
+            list.pop();
+            Context::list(list)
+        }
+        (Function::Rest, _) | (Function::Trunc, _) => Context::null(),
+        (Function::Reverse, Ok(mut list)) => {
+            list.reverse();
+            Context::list(list)
+        }
+        (Function::Strip, Ok(list)) => {
+            Context::list(list.into_iter().filter(|item| !item.is_null()).collect())
+        }
+        (_, Ok(list)) => Context::list(list),
+        (_, Err(value)) => value,
+    }
+}
 
 pub struct Interpreter {
     group: Group,
+    dynamic_scoping: bool,
+    locale: String,
+    new_writer: NewWriter,
+    line_width: Option<usize>,
 }
 
 impl Interpreter {
     pub fn new(group: Group) -> Interpreter {
-        Interpreter { group }
+        let dynamic_scoping = group.has_dynamic_scoping();
+        Interpreter {
+            group,
+            dynamic_scoping,
+            locale: DEFAULT_LOCALE.to_owned(),
+            new_writer: AutoIndentWriter::boxed,
+            line_width: None,
+        }
     }
 
-    pub fn render(&self, template: &CompiledTemplate, attributes: &Attributes) -> String {
+    /// Pass `locale` to the attribute renderers of the group, rather
+    /// than `DEFAULT_LOCALE`.
+    pub fn with_locale(self, locale: impl Into<String>) -> Interpreter {
+        Interpreter {
+            locale: locale.into(),
+            ..self
+        }
+    }
+
+    /// Render through writers created by `new_writer`, rather than
+    /// ones that indent templates to line up with their expression.
+    pub fn with_writer(self, new_writer: NewWriter) -> Interpreter {
+        Interpreter { new_writer, ..self }
+    }
+
+    /// Wrap lines at `width` columns where expressions have the `wrap`
+    /// option.
+    pub fn with_line_width(self, width: usize) -> Interpreter {
+        Interpreter {
+            line_width: Some(width),
+            ..self
+        }
+    }
+
+    fn new_writer<'a>(&self, out: &'a mut dyn fmt::Write) -> Box<dyn StWriter + 'a> {
+        let mut writer = (self.new_writer)(out);
+        if let Some(width) = self.line_width {
+            writer.set_line_width(width);
+        }
+        writer
+    }
+
+    pub fn render(
+        &self,
+        template: &CompiledTemplate,
+        attributes: &Attributes,
+    ) -> Result<String, Error> {
         let mut out = String::new();
-        for expr in &template.expressions {
-            match expr {
-                Expr::Literal(s) => out.push_str(s),
-                Expr::Attribute(name) => {
-                    let attribute = attributes.get(name);
-                    out.push_str(&attribute.to_string());
+        self.render_to(template, attributes, &mut out)?;
+        Ok(out)
+    }
+
+    /// Render `template` to `out` as it's interpreted, rather than
+    /// collecting the whole output first.
+    pub fn render_to(
+        &self,
+        template: &CompiledTemplate,
+        attributes: &Attributes,
+        out: &mut dyn fmt::Write,
+    ) -> Result<(), Error> {
+        self.render_native(template, attributes, &self.group, out)
+    }
+
+    /// Render `template`, which is defined by `native_group` rather
+    /// than the group of the interpreter if it was found in an import.
+    pub(crate) fn render_native(
+        &self,
+        template: &CompiledTemplate,
+        attributes: &Attributes,
+        native_group: &Group,
+        out: &mut dyn fmt::Write,
+    ) -> Result<(), Error> {
+        let mut out = self.new_writer(out);
+        self.render_template(template, attributes, native_group, None, &mut *out)
+    }
+
+    /// Render to a string through a new writer, for output that's used
+    /// as a value.
+    fn render_value(
+        &self,
+        render: impl FnOnce(&mut dyn StWriter) -> Result<(), Error>,
+    ) -> Result<Context, Error> {
+        let mut rendered = String::new();
+        render(&mut *self.new_writer(&mut rendered))?;
+        Ok(Context::new(Json::String(rendered)))
+    }
+
+    fn render_template(
+        &self,
+        template: &CompiledTemplate,
+        attributes: &Attributes,
+        native_group: &Group,
+        parent: Option<&Scope>,
+        out: &mut dyn StWriter,
+    ) -> Result<(), Error> {
+        let defaults = self.default_arguments(template, attributes, native_group, parent)?;
+        let attributes = if defaults.is_empty() {
+            Cow::Borrowed(attributes)
+        } else {
+            let mut attributes = attributes.clone();
+            for (name, value) in defaults {
+                attributes.insert(name, value);
+            }
+            Cow::Owned(attributes)
+        };
+        let scope = Scope::new(template, &attributes, native_group, parent);
+        self.render_expressions(&template.expressions, true, &scope, out)
+    }
+
+    /// Evaluate the default values of the formal arguments of
+    /// `template` which haven't been set.
+    fn default_arguments(
+        &self,
+        template: &CompiledTemplate,
+        attributes: &Attributes,
+        native_group: &Group,
+        parent: Option<&Scope>,
+    ) -> Result<Attributes, Error> {
+        let mut defaults = Attributes::new();
+        if let Some(formal_arguments) = &template.formal_arguments {
+            let scope = Scope::new(template, attributes, native_group, parent);
+            for (name, default) in formal_arguments {
+                if let (false, Some(default)) = (attributes.contains(name), default) {
+                    defaults.insert(name.as_str(), self.evaluate(default, &scope)?);
+                }
+            }
+        }
+        Ok(defaults)
+    }
+
+    /// Render `expressions`, indenting each one that starts a line by
+    /// the whitespace before it. `at_start_of_line` tells whether the
+    /// expressions start a line of the template.
+    fn render_expressions(
+        &self,
+        expressions: &[Expr],
+        at_start_of_line: bool,
+        scope: &Scope,
+        out: &mut dyn StWriter,
+    ) -> Result<(), Error> {
+        let mut indent = None;
+        for (index, expr) in expressions.iter().enumerate() {
+            if let Some(indent) = indent.take() {
+                out.push_indentation(indent);
+                self.render_expression(expr, scope, out)?;
+                out.pop_indentation();
+                continue;
+            }
+            if let Expr::Literal(s) = expr {
+                let (line_start, line) = match s.rfind('\n') {
+                    Some(newline) => (true, &s[newline + 1..]),
+                    None => (index == 0 && at_start_of_line, s.as_str()),
+                };
+                let is_indent = !line.is_empty() && line.chars().all(|c| c == ' ' || c == '\t');
+                if line_start && is_indent {
+                    indent = Some(line);
+                }
+            }
+            self.render_expression(expr, scope, out)?;
+        }
+        Ok(())
+    }
+
+    fn render_expression(
+        &self,
+        expr: &Expr,
+        scope: &Scope,
+        out: &mut dyn StWriter,
+    ) -> Result<(), Error> {
+        match expr {
+            Expr::Literal(s) => out.write_str(s)?,
+            Expr::If(condition, consequent, alternative) => {
+                let branch = if self.test(condition, scope)? {
+                    consequent
+                } else {
+                    alternative
+                };
+                let at_start_of_line = out.at_start_of_line();
+                self.render_expressions(branch, at_start_of_line, scope, out)?;
+            }
+            Expr::Include(..) | Expr::IndirectInclude(..) | Expr::SubTemplate(..) => {
+                self.apply_to(expr, None, scope, out)?
+            }
+            Expr::Region(name, default) => match self.group.lookup(name)? {
+                // The group being rendered is the most derived, so its
+                // definition overrides those of the groups it imports.
+                Some((native_group, template)) => {
+                    let parent = if self.dynamic_scoping {
+                        Some(scope)
+                    } else {
+                        None
+                    };
+                    let attributes = Attributes::new();
+                    self.render_template(&template, &attributes, &native_group, parent, out)?
+                }
+                None => self.render_expressions(default, false, scope, out)?,
+            },
+            Expr::Options(expr, options) => {
+                let options = self.evaluate_options(expr, options, scope)?;
+                if options.anchor {
+                    out.push_anchor_point();
+                }
+                self.write(self.evaluate(expr, scope)?, &options, out)?;
+                if options.anchor {
+                    out.pop_anchor_point();
+                }
+            }
+            _ => {
+                let options = Options {
+                    rendered: is_template_output(expr),
+                    ..Options::default()
+                };
+                self.write(self.evaluate(expr, scope)?, &options, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate_options(
+        &self,
+        expr: &Expr,
+        options: &[(ExprOption, Expr)],
+        scope: &Scope,
+    ) -> Result<Options, Error> {
+        let mut evaluated = Options {
+            rendered: is_template_output(expr),
+            ..Options::default()
+        };
+        for (option, value) in options {
+            let value = self.evaluate(value, scope)?.to_string();
+            match option {
+                ExprOption::Format => evaluated.format = Some(value),
+                ExprOption::Null => evaluated.null = Some(value),
+                ExprOption::Separator => evaluated.separator = Some(value),
+                ExprOption::Wrap => evaluated.wrap = Some(value),
+                ExprOption::Anchor => evaluated.anchor = value == "true",
+            }
+        }
+        Ok(evaluated)
+    }
+
+    fn write(
+        &self,
+        value: Context,
+        options: &Options,
+        out: &mut dyn StWriter,
+    ) -> Result<(), Error> {
+        if value.is_null() {
+            if let Some(null) = &options.null {
+                if let Some(wrap) = &options.wrap {
+                    out.write_wrap(wrap, null.chars().count())?;
+                }
+                out.write_str(null)?;
+            }
+            return Ok(());
+        }
+        let value = match value.into_list() {
+            Ok(list) => {
+                let mut seen_a_value = false;
+                for item in list {
+                    if item.is_null() && options.null.is_none() {
+                        continue;
+                    }
+                    if let (true, Some(separator)) = (seen_a_value, &options.separator) {
+                        out.write_str(separator)?;
+                    }
+                    self.write(item, options, out)?;
+                    seen_a_value = true;
                 }
-                Expr::AttributePath(attribute_name, path) => {
-                    let path: Vec<&str> = path.iter().map(AsRef::as_ref).collect();
-                    let rendered = attributes.get(attribute_name).navigate(&path);
-                    out.push_str(&rendered.to_string());
+                return Ok(());
+            }
+            Err(value) => value,
+        };
+        let rendered = match value.as_json() {
+            _ if options.rendered => value.to_string(),
+            Some(json) => {
+                let format = options.format.as_deref();
+                let renderer = ValueKind::of(json).and_then(|kind| self.group.renderer(kind));
+                match (renderer, json) {
+                    (Some(renderer), json) => renderer.render(json, format, &self.locale),
+                    (None, Json::String(_)) => StringRenderer.render(json, format, &self.locale),
+                    (None, json) => json.render(),
+                }
+            }
+            None => value.to_string(),
+        };
+        if let Some(wrap) = &options.wrap {
+            out.write_wrap(wrap, rendered.chars().count())?;
+        }
+        out.write_str(&rendered)?;
+        Ok(())
+    }
+
+    fn evaluate(&self, expr: &Expr, scope: &Scope) -> Result<Context, Error> {
+        let value = match expr {
+            Expr::Literal(s) | Expr::String(s) => Context::new(Json::String(s.clone())),
+            Expr::Attribute(name) => match self.dictionary(name, scope) {
+                Some(dictionary) => {
+                    let entries = dictionary
+                        .entries()
+                        .map(|(key, value)| {
+                            Ok((key.clone(), self.evaluate(value, scope)?.into_json()))
+                        })
+                        .collect::<Result<_, Error>>()?;
+                    Context::new(Json::Object(entries))
                 }
-                Expr::Include(name, _arg_names) => {
-                    let template = self.group.get(name).unwrap_or_else(Template::default);
-                    out.push_str(&template.render());
+                None => scope.get(name).clone(),
+            },
+            Expr::AttributePath(attribute_name, path) => {
+                let path: Vec<&str> = path.iter().map(AsRef::as_ref).collect();
+                self.navigate(attribute_name, &path, scope)?
+            }
+            Expr::IndirectProperty(object, property) => {
+                let property = self.evaluate(property, scope)?.to_string();
+                match &**object {
+                    Expr::Attribute(attribute_name) => {
+                        self.navigate(attribute_name, &[&property], scope)?
+                    }
+                    object => self.evaluate(object, scope)?.navigate(&[&property]),
                 }
             }
+            Expr::Include(..) | Expr::IndirectInclude(..) | Expr::SubTemplate(..) => {
+                self.apply(expr, None, scope)?
+            }
+            Expr::If(..) | Expr::Options(..) | Expr::Region(..) => {
+                self.render_value(|out| self.render_expression(expr, scope, out))?
+            }
+            Expr::Bool(value) => Context::new(Json::Bool(*value)),
+            Expr::List(items) => {
+                let mut list = vec![];
+                for item in items {
+                    match self.evaluate(item, scope)?.into_list() {
+                        Ok(items) => list.extend(items),
+                        Err(item) => list.push(item),
+                    }
+                }
+                Context::list(list)
+            }
+            Expr::Function(function, argument) => call(*function, self.evaluate(argument, scope)?),
+            Expr::Not(_) | Expr::And(..) | Expr::Or(..) => {
+                Context::new(Json::Bool(self.test(expr, scope)?))
+            }
+            Expr::Map(value, template) => {
+                let value = self.evaluate(value, scope)?;
+                self.map(value, std::slice::from_ref(template), scope)?
+            }
+            Expr::RoundRobinMap(value, templates) => {
+                let value = self.evaluate(value, scope)?;
+                self.map(value, templates, scope)?
+            }
+            Expr::ParallelMap(values, template) => {
+                let mut lists = vec![];
+                for value in values {
+                    let list = match self.evaluate(value, scope)?.into_list() {
+                        Ok(list) => list,
+                        Err(value) if value.is_null() => vec![],
+                        Err(item) => vec![item],
+                    };
+                    lists.push(list.into_iter());
+                }
+                // Shorter lists are padded with nulls.
+                let length = lists.iter().map(ExactSizeIterator::len).max().unwrap_or(0);
+                let mapped = (0..length)
+                    .map(|index| {
+                        let values = lists
+                            .iter_mut()
+                            .map(|list| list.next().unwrap_or_default())
+                            .collect();
+                        let element = Some(Element { values, index });
+                        self.apply(template, element, scope)
+                    })
+                    .collect::<Result<_, Error>>()?;
+                Context::list(mapped)
+            }
+        };
+        Ok(value)
+    }
+
+    /// Apply `templates` in turn to each element of `value`.
+    fn map(&self, value: Context, templates: &[Expr], scope: &Scope) -> Result<Context, Error> {
+        let mapped = match value.into_list() {
+            Ok(list) => {
+                // Nulls are skipped, so they don't count towards the index.
+                let mut index = 0;
+                let mapped = list
+                    .into_iter()
+                    .map(|item| {
+                        if item.is_null() {
+                            return Ok(item);
+                        }
+                        let template = &templates[index % templates.len()];
+                        let element = Some(Element::new(item, index));
+                        index += 1;
+                        self.apply(template, element, scope)
+                    })
+                    .collect::<Result<_, Error>>()?;
+                Context::list(mapped)
+            }
+            Err(value) if value.is_null() => value,
+            Err(item) => self.apply(&templates[0], Some(Element::new(item, 0)), scope)?,
+        };
+        Ok(mapped)
+    }
+
+    /// The value at `path` within the attribute or dictionary named
+    /// `attribute_name`.
+    fn navigate(
+        &self,
+        attribute_name: &str,
+        path: &[&str],
+        scope: &Scope,
+    ) -> Result<Context, Error> {
+        let value = match (self.dictionary(attribute_name, scope), path.split_first()) {
+            (Some(dictionary), Some((key, path))) => match dictionary.get(key) {
+                Some(value) => self.evaluate(&value, scope)?.navigate(path),
+                None => Context::null(),
+            },
+            _ => scope.get(attribute_name).navigate(path),
+        };
+        Ok(value)
+    }
+
+    /// The dictionary of the group named `name`, unless an attribute
+    /// of the same name is in scope.
+    fn dictionary(&self, name: &str, scope: &Scope) -> Option<Dictionary> {
+        if scope.resolves(name) {
+            None
+        } else {
+            self.group.dictionary(name)
+        }
+    }
+
+    /// Render `template`, binding `element` to its parameters when
+    /// it's being applied to the elements of a list.
+    fn apply(
+        &self,
+        template: &Expr,
+        element: Option<Element>,
+        scope: &Scope,
+    ) -> Result<Context, Error> {
+        self.render_value(|out| self.apply_to(template, element, scope, out))
+    }
+
+    fn apply_to(
+        &self,
+        template: &Expr,
+        element: Option<Element>,
+        scope: &Scope,
+        out: &mut dyn StWriter,
+    ) -> Result<(), Error> {
+        match template {
+            Expr::SubTemplate(parameters, body) => {
+                let mut attributes = Attributes::new();
+                if let Some(element) = element {
+                    element.bind_index(&mut attributes);
+                    let mut values = element.values.into_iter();
+                    if parameters.is_empty() {
+                        if let Some(value) = values.next() {
+                            attributes.insert(IMPLICIT_ARGUMENT, value);
+                        }
+                    }
+                    for (parameter, value) in parameters.iter().zip(values) {
+                        attributes.insert(parameter.as_str(), value);
+                    }
+                }
+                self.render_expressions(body, false, &scope.nested(&attributes), out)
+            }
+            Expr::Include(name, arguments) => self.include(name, arguments, element, scope, out),
+            Expr::IndirectInclude(name, arguments) => {
+                let name = self.evaluate(name, scope)?.to_string();
+                self.include(&name, arguments, element, scope, out)
+            }
+            expr => unreachable!("cannot apply non-template expression: {:?}", expr),
+        }
+    }
+
+    /// Render the template `name`, which renders nothing if there's no
+    /// such template.
+    fn include(
+        &self,
+        name: &str,
+        arguments: &Arguments,
+        element: Option<Element>,
+        scope: &Scope,
+        out: &mut dyn StWriter,
+    ) -> Result<(), Error> {
+        let found = match name.strip_prefix(SUPER) {
+            Some(name) => scope.native_group.lookup_imported(name)?,
+            None => self.group.lookup(name)?,
+        };
+        let (native_group, template) = match found {
+            Some(found) => found,
+            None => return Ok(()),
+        };
+        let attributes = self.bind_arguments(name, &template, element, arguments, scope)?;
+        let parent = if self.dynamic_scoping {
+            Some(scope)
+        } else {
+            None
+        };
+        self.render_template(&template, &attributes, &native_group, parent, out)
+    }
+
+    /// Evaluate the arguments of an include into the attributes of
+    /// the included template.
+    fn bind_arguments(
+        &self,
+        name: &str,
+        template: &CompiledTemplate,
+        element: Option<Element>,
+        arguments: &Arguments,
+        scope: &Scope,
+    ) -> Result<Attributes, Error> {
+        let mut attributes = Attributes::new();
+        // An element being mapped over is always the first argument.
+        let mut positional = vec![];
+        if let Some(element) = element {
+            element.bind_index(&mut attributes);
+            positional.extend(element.values);
+        }
+        match arguments {
+            Arguments::Positional(values) => {
+                for value in values {
+                    positional.push(self.evaluate(value, scope)?);
+                }
+            }
+            Arguments::Named(values) | Arguments::PassThrough(values) => {
+                for (parameter, value) in values {
+                    template.assert_is_argument(parameter)?;
+                    attributes.insert(parameter.as_str(), self.evaluate(value, scope)?);
+                }
+            }
+        }
+
+        let count = positional.len();
+        match &template.formal_arguments {
+            Some(formal_arguments) => {
+                let expected = formal_arguments.len();
+                let required = formal_arguments
+                    .values()
+                    .filter(|default| default.is_none())
+                    .count();
+                let is_positional = matches!(arguments, Arguments::Positional(_));
+                if count > expected || (is_positional && count < required) {
+                    return Err(Error::ArgumentCountMismatch(name.into(), expected, count));
+                }
+                for (parameter, value) in formal_arguments.keys().zip(positional) {
+                    attributes.insert(parameter.as_str(), value);
+                }
+            }
+            None if count > 1 => {
+                return Err(Error::ArgumentCountMismatch(name.into(), 1, count));
+            }
+            None => {
+                if let Some(value) = positional.pop() {
+                    attributes.insert(IMPLICIT_ARGUMENT, value);
+                }
+            }
+        }
+
+        if let (Arguments::PassThrough(_), Some(formal_arguments)) =
+            (arguments, &template.formal_arguments)
+        {
+            for parameter in formal_arguments.keys() {
+                let value = scope.get(parameter);
+                if !attributes.contains(parameter) && !value.is_null() {
+                    attributes.insert(parameter.as_str(), value.clone());
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    fn test(&self, condition: &Expr, scope: &Scope) -> Result<bool, Error> {
+        match condition {
+            Expr::Not(operand) => Ok(!self.test(operand, scope)?),
+            Expr::And(lhs, rhs) => Ok(self.test(lhs, scope)? && self.test(rhs, scope)?),
+            Expr::Or(lhs, rhs) => Ok(self.test(lhs, scope)? || self.test(rhs, scope)?),
+            _ => Ok(self.evaluate(condition, scope)?.is_truthy()),
         }
-        out
     }
 }
@@ -1,37 +1,126 @@
 #![recursion_limit = "128"]
 
-use std::borrow::Borrow;
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::hash::Hash;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::str::FromStr;
 
-use indexmap::IndexSet;
-
-use lazy_static::lazy_static;
+use indexmap::IndexMap;
 
 use serde::ser::{Serialize, Serializer};
 
 mod context;
-pub use crate::context::Context;
+pub use crate::context::{Context, ModelAdaptor};
 
 mod error;
-pub use crate::error::Error;
+pub use crate::error::{Error, Location};
 
 mod interpreter;
 pub use crate::interpreter::Interpreter;
 
+mod loader;
+
+mod renderer;
+pub use crate::renderer::{AttributeRenderer, StringRenderer, ValueKind, DEFAULT_LOCALE};
+
+mod writer;
+pub use crate::writer::{AutoIndentWriter, NewWriter, NoIndentWriter, StWriter};
+
 mod parse;
-pub use crate::parse::pest::TemplateParser;
+pub use crate::parse::group::GroupParser;
+pub use crate::parse::pest::{Delimiters, TemplateParser};
 pub use crate::parse::syn::{AsDynamicTemplate, Group as StaticGroup, GroupBody};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Expr {
     Literal(String),
+    /// A string within an expression, as in `<"a">` or `<t("a")>`.
+    String(String),
     Attribute(String),
     AttributePath(String, Vec<String>),
-    Include(String, Vec<String>),
+    /// The property of a value named by the value of an expression,
+    /// as in `<obj.(name)>`.
+    IndirectProperty(Box<Expr>, Box<Expr>),
+    Include(String, Arguments),
+    /// An include of the template named by the value of an expression,
+    /// as in `<(name)()>`.
+    IndirectInclude(Box<Expr>, Arguments),
+    If(Box<Expr>, Vec<Expr>, Vec<Expr>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    SubTemplate(Vec<String>, Vec<Expr>),
+    Map(Box<Expr>, Box<Expr>),
+    ParallelMap(Vec<Expr>, Box<Expr>),
+    RoundRobinMap(Box<Expr>, Vec<Expr>),
+    Options(Box<Expr>, Vec<(ExprOption, Expr)>),
+    Bool(bool),
+    /// A list of the values of expressions, with the elements of any
+    /// lists among them in their place, as in `<["a", b, c.names]>`.
+    List(Vec<Expr>),
+    Function(Function, Box<Expr>),
+    /// A region of a template, which renders the template of the same
+    /// name if a group defines it, and its default content otherwise.
+    ///
+    /// Regions are named by the template they're in once it's added to
+    /// a group, as in `@t.r` for the region `r` of `t`.
+    Region(String, Vec<Expr>),
+}
+
+/// The options that can follow an expression, as in `<names; separator=", ">`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExprOption {
+    Anchor,
+    Format,
+    Null,
+    Separator,
+    Wrap,
+}
+
+/// The built-in functions, as in `<first(names)>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Function {
+    /// The first element of a list.
+    First,
+    /// The last element of a list.
+    Last,
+    /// Every element of a list but the first.
+    Rest,
+    /// Every element of a list but the last.
+    Trunc,
+    /// The number of elements of a list, including nulls.
+    Length,
+    /// The elements of a list in reverse order.
+    Reverse,
+    /// The elements of a list that aren't null.
+    Strip,
+    /// A string without leading and trailing whitespace.
+    Trim,
+    /// The number of characters in a string.
+    Strlen,
+}
+
+/// The arguments passed to an included template, either by position
+/// as in `<b(x, y)>` or by name as in `<b(first=x, second=y)>`.
+///
+/// Named arguments can be followed by `...`, as in `<b(first=x, ...)>`,
+/// to pass through any attributes of the caller named like one of the
+/// remaining formal arguments of the included template.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Arguments {
+    Positional(Vec<Expr>),
+    Named(Vec<(String, Expr)>),
+    PassThrough(Vec<(String, Expr)>),
+}
+
+impl Default for Arguments {
+    fn default() -> Self {
+        Arguments::Positional(vec![])
+    }
 }
 
 impl Default for Expr {
@@ -50,10 +139,14 @@ pub struct CompiledTemplate {
     // have meaning to try and say that, since if this vec or an item
     // in it was moved outside of this struct then the lifetimes do
     // matter.
-    formal_arguments: Option<IndexSet<String>>,
+    formal_arguments: Option<FormalArguments>,
     expressions: Vec<Expr>,
 }
 
+/// The formal arguments of a template, in order, along with the
+/// expressions giving their default values.
+type FormalArguments = IndexMap<String, Option<Expr>>;
+
 impl CompiledTemplate {
     pub fn new(template: impl Into<String>, expressions: Vec<Expr>) -> CompiledTemplate {
         CompiledTemplate {
@@ -65,7 +158,7 @@ impl CompiledTemplate {
 
     pub fn with_args(
         template: impl Into<String>,
-        formal_arguments: impl IntoIterator<Item = String>,
+        formal_arguments: impl IntoIterator<Item = (String, Option<Expr>)>,
         expressions: Vec<Expr>,
     ) -> CompiledTemplate {
         CompiledTemplate {
@@ -75,21 +168,93 @@ impl CompiledTemplate {
         }
     }
 
+    /// The template named `name` in a group, whose regions are named
+    /// after it.
+    fn named(mut self, name: &str) -> CompiledTemplate {
+        name_regions(name, &mut self.expressions);
+        self
+    }
+
     pub fn assert_is_argument(&self, arg_name: impl AsRef<str>) -> Result<(), Error> {
         let arg_name = arg_name.as_ref();
         match &self.formal_arguments {
-            Some(formal_arguments) if formal_arguments.contains(arg_name) => Ok(()),
+            Some(formal_arguments) if formal_arguments.contains_key(arg_name) => Ok(()),
             Some(_) => Err(Error::NoSuchAttribute(arg_name.to_string())),
             None => Ok(()),
         }
     }
 }
 
+/// Name the regions in `expressions` after the template `template_name`
+/// they're in, as in `@t.r`.
+fn name_regions(template_name: &str, expressions: &mut [Expr]) {
+    for expr in expressions {
+        name_region(template_name, expr);
+    }
+}
+
+/// Name the regions in `expr` and the expressions within it.
+fn name_region(template_name: &str, expr: &mut Expr) {
+    match expr {
+        Expr::Region(name, default) => {
+            if !name.starts_with('@') {
+                *name = format!("@{}.{}", template_name, name);
+            }
+            name_regions(template_name, default);
+        }
+        Expr::Include(_, arguments) => name_argument_regions(template_name, arguments),
+        Expr::IndirectInclude(name, arguments) => {
+            name_region(template_name, name);
+            name_argument_regions(template_name, arguments);
+        }
+        Expr::If(condition, consequent, alternative) => {
+            name_region(template_name, condition);
+            name_regions(template_name, consequent);
+            name_regions(template_name, alternative);
+        }
+        Expr::Not(expr) | Expr::Function(_, expr) => name_region(template_name, expr),
+        Expr::IndirectProperty(expr, property)
+        | Expr::And(expr, property)
+        | Expr::Or(expr, property)
+        | Expr::Map(expr, property) => {
+            name_region(template_name, expr);
+            name_region(template_name, property);
+        }
+        Expr::SubTemplate(_, body) | Expr::List(body) => name_regions(template_name, body),
+        Expr::ParallelMap(exprs, template) | Expr::RoundRobinMap(template, exprs) => {
+            name_regions(template_name, exprs);
+            name_region(template_name, template);
+        }
+        Expr::Options(expr, options) => {
+            name_region(template_name, expr);
+            for (_, value) in options {
+                name_region(template_name, value);
+            }
+        }
+        Expr::Literal(_)
+        | Expr::String(_)
+        | Expr::Attribute(_)
+        | Expr::AttributePath(_, _)
+        | Expr::Bool(_) => {}
+    }
+}
+
+fn name_argument_regions(template_name: &str, arguments: &mut Arguments) {
+    match arguments {
+        Arguments::Positional(values) => name_regions(template_name, values),
+        Arguments::Named(values) | Arguments::PassThrough(values) => {
+            for (_, value) in values {
+                name_region(template_name, value);
+            }
+        }
+    }
+}
+
 impl FromStr for CompiledTemplate {
     type Err = Error;
 
     fn from_str(template: &str) -> Result<CompiledTemplate, Self::Err> {
-        let expressions = TemplateParser::expressions_of(template)?;
+        let expressions = TemplateParser::expressions_of(template, Delimiters::default())?;
         Ok(CompiledTemplate::new(template, expressions))
     }
 }
@@ -108,16 +273,23 @@ impl Attributes {
         self.0.entry(name.into()).or_default().concat(value);
     }
 
+    /// The value of the attribute `name`, which is null if it hasn't
+    /// been set.
     pub fn get(&self, name: impl AsRef<str>) -> &Context {
-        lazy_static! {
-            static ref NULL_CONTEXT: Context = Context::null();
-        }
-        self.0.get(name.as_ref()).unwrap_or(&NULL_CONTEXT)
+        self.0.get(name.as_ref()).unwrap_or(&Context::NULL)
+    }
+
+    pub fn contains(&self, name: impl AsRef<str>) -> bool {
+        self.0.contains_key(name.as_ref())
     }
 
     pub fn len(&self) -> usize {
         self.0.len()
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
 impl<'a> IntoIterator for &'a Attributes {
@@ -138,26 +310,313 @@ impl IntoIterator for Attributes {
     }
 }
 
+/// A map declared in a group, as in
+/// `typeInit ::= [ "int":"0", default:"null" ]`, which templates look
+/// up like an attribute, as in `<typeInit.int>`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Dictionary {
+    entries: IndexMap<String, Expr>,
+    default: Option<DictionaryDefault>,
+}
+
+/// The value of a dictionary for keys it has no entry for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DictionaryDefault {
+    /// The key itself, as in `default:key`.
+    Key,
+    Value(Expr),
+}
+
+impl Dictionary {
+    pub fn new() -> Dictionary {
+        Dictionary::default()
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: Expr) {
+        self.entries.insert(key.into(), value);
+    }
+
+    pub fn set_default(&mut self, default: DictionaryDefault) {
+        self.default = Some(default);
+    }
+
+    /// The expression giving the value of `key`, falling back to the
+    /// default value of the dictionary.
+    pub fn get(&self, key: &str) -> Option<Expr> {
+        match (self.entries.get(key), &self.default) {
+            (Some(value), _) => Some(value.clone()),
+            (None, Some(DictionaryDefault::Key)) => Some(Expr::Literal(key.to_string())),
+            (None, Some(DictionaryDefault::Value(value))) => Some(value.clone()),
+            (None, None) => None,
+        }
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&String, &Expr)> {
+        self.entries.iter()
+    }
+}
+
 type TemplateMap = HashMap<String, CompiledTemplate>;
 
+type DictionaryMap = HashMap<String, Dictionary>;
+
+/// The attribute renderers registered on a group.
+#[derive(Clone, Default)]
+struct RendererMap(HashMap<ValueKind, Rc<dyn AttributeRenderer>>);
+
+impl fmt::Debug for RendererMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+impl PartialEq for RendererMap {
+    fn eq(&self, other: &RendererMap) -> bool {
+        self.0.len() == other.0.len()
+            && self.0.iter().all(|(kind, renderer)| {
+                other
+                    .0
+                    .get(kind)
+                    .is_some_and(|other| Rc::ptr_eq(renderer, other))
+            })
+    }
+}
+
+impl Eq for RendererMap {}
+
+#[derive(Debug, PartialEq, Eq)]
+struct GroupImp {
+    templates: TemplateMap,
+    dictionaries: DictionaryMap,
+    renderers: RendererMap,
+    dynamic_scoping: bool,
+    /// The delimiters of the expressions in templates added to or
+    /// loaded by this group.
+    delimiters: Delimiters,
+    /// The directory templates that haven't been loaded yet are read
+    /// from.
+    root: Option<PathBuf>,
+    /// The groups templates and dictionaries are looked up in when
+    /// this group doesn't define them, in the order they're imported.
+    imports: Vec<Group>,
+}
+
+impl Default for GroupImp {
+    fn default() -> GroupImp {
+        GroupImp {
+            templates: TemplateMap::default(),
+            dictionaries: DictionaryMap::default(),
+            renderers: RendererMap::default(),
+            dynamic_scoping: true,
+            delimiters: Delimiters::default(),
+            root: None,
+            imports: Vec::new(),
+        }
+    }
+}
+
 #[derive(Debug, Default, PartialEq, Eq)]
-pub struct Group(Rc<RefCell<TemplateMap>>);
+pub struct Group(Rc<RefCell<GroupImp>>);
 
 impl Group {
     pub fn new() -> Group {
         Group::default()
     }
 
-    pub fn get<Q: ?Sized>(&self, template_name: &Q) -> Option<Template>
-    where
-        String: Borrow<Q>,
-        Q: Hash + Eq,
-    {
-        let group = self.clone();
-        RefCell::borrow(&*self.0)
-            .get(template_name)
-            .cloned()
-            .map(move |imp| Template::new(group, imp))
+    /// Load the group of templates in the file at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Group, Error> {
+        loader::load_group_file(path.as_ref())
+    }
+
+    /// A group of the templates in the directory at `path`, which are
+    /// loaded from `name.st` files as they're first used.
+    ///
+    /// Templates in subdirectories are named by their path, as in
+    /// `/sub/t` for the template in `sub/t.st`.
+    pub fn from_dir(path: impl AsRef<Path>) -> Result<Group, Error> {
+        let root = path.as_ref();
+        if !fs::metadata(root)?.is_dir() {
+            let error = io::Error::new(io::ErrorKind::InvalidInput, "not a directory");
+            return Err(Error::Io(error));
+        }
+        let imp = GroupImp {
+            root: Some(root.to_owned()),
+            ..GroupImp::default()
+        };
+        Ok(Group(Rc::new(RefCell::new(imp))))
+    }
+
+    /// The template `template_name`, or `None` if there's no such
+    /// template or it fails to load. Use `load` to see why a template
+    /// failed to load.
+    pub fn get(&self, template_name: &str) -> Option<Template> {
+        self.load(template_name).unwrap_or(None)
+    }
+
+    /// The template `template_name`, loading it from the directory of
+    /// the group if it hasn't been loaded yet, or else from the groups
+    /// it imports.
+    pub fn load(&self, template_name: &str) -> Result<Option<Template>, Error> {
+        let found = self.lookup(template_name)?;
+        Ok(found.map(|(native_group, imp)| Template {
+            native_group,
+            ..Template::new(self.clone(), imp)
+        }))
+    }
+
+    /// The template `template_name` along with the group defining it,
+    /// which is either this group or one it imports.
+    pub(crate) fn lookup(
+        &self,
+        template_name: &str,
+    ) -> Result<Option<(Group, CompiledTemplate)>, Error> {
+        match self.load_own(template_name)? {
+            Some(imp) => Ok(Some((self.clone(), imp))),
+            None => self.lookup_imported(template_name),
+        }
+    }
+
+    /// The template `template_name` in the groups this group imports,
+    /// which is the template it overrides if it defines its own.
+    pub(crate) fn lookup_imported(
+        &self,
+        template_name: &str,
+    ) -> Result<Option<(Group, CompiledTemplate)>, Error> {
+        let imports = RefCell::borrow(&*self.0).imports.clone();
+        for group in imports {
+            if let Some(found) = group.lookup(template_name)? {
+                return Ok(Some(found));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The template `template_name` defined by this group itself.
+    fn load_own(&self, template_name: &str) -> Result<Option<CompiledTemplate>, Error> {
+        let name = template_name.trim_start_matches('/');
+        if let Some(imp) = RefCell::borrow(&*self.0).templates.get(name) {
+            return Ok(Some(imp.clone()));
+        }
+        let is_path = name
+            .split('/')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_'));
+        let path = match &RefCell::borrow(&*self.0).root {
+            Some(root) if is_path => root.join(format!("{}.st", name)),
+            _ => return Ok(None),
+        };
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let delimiters = self.delimiters();
+        let imp = loader::load_template_file(&path, delimiters)?.named(name);
+        self.0
+            .borrow_mut()
+            .templates
+            .insert(name.to_string(), imp.clone());
+        Ok(Some(imp))
+    }
+
+    /// Fall back to the templates and dictionaries of `group` when
+    /// this group doesn't define them, after any groups already
+    /// imported.
+    ///
+    /// Templates found in `group` still look up the templates they
+    /// include in this group first, so this group can override them.
+    ///
+    /// Importing a group that is this group or imports it, directly or
+    /// through the groups it imports, fails.
+    pub fn import(&self, group: &Group) -> Result<(), Error> {
+        if group.imports(self) {
+            return Err(Error::CircularImport);
+        }
+        self.0.borrow_mut().imports.push(group.clone());
+        Ok(())
+    }
+
+    /// Whether `group` is this group or one it imports.
+    fn imports(&self, group: &Group) -> bool {
+        Rc::ptr_eq(&self.0, &group.0)
+            || RefCell::borrow(&*self.0)
+                .imports
+                .iter()
+                .any(|import| import.imports(group))
+    }
+
+    /// Import the group in the group file or template directory at
+    /// `path`.
+    pub fn import_path(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        let group = if path.is_dir() {
+            Group::from_dir(path)?
+        } else {
+            Group::from_file(path)?
+        };
+        self.import(&group)
+    }
+
+    /// Parse `template` with the delimiters of the group, and add it to
+    /// the group, replacing any template of the same name.
+    pub fn add_template(&self, name: impl Into<String>, template: &str) -> Result<(), Error> {
+        let expressions = TemplateParser::expressions_of(template, self.delimiters())?;
+        let name = name.into();
+        let imp = CompiledTemplate::new(template, expressions).named(&name);
+        self.0.borrow_mut().templates.insert(name, imp);
+        Ok(())
+    }
+
+    pub fn delimiters(&self) -> Delimiters {
+        RefCell::borrow(&*self.0).delimiters
+    }
+
+    /// Parse the templates added to or loaded by the group from now on
+    /// with `delimiters`, rather than `<` and `>`.
+    pub fn set_delimiters(&self, delimiters: Delimiters) {
+        self.0.borrow_mut().delimiters = delimiters;
+    }
+
+    pub fn dictionary(&self, name: &str) -> Option<Dictionary> {
+        let imp = RefCell::borrow(&*self.0);
+        match imp.dictionaries.get(name) {
+            Some(dictionary) => Some(dictionary.clone()),
+            None => imp.imports.iter().find_map(|group| group.dictionary(name)),
+        }
+    }
+
+    /// Add a dictionary to the group, replacing any dictionary of the
+    /// same name.
+    pub fn add_dictionary(&self, name: impl Into<String>, dictionary: Dictionary) {
+        self.0
+            .borrow_mut()
+            .dictionaries
+            .insert(name.into(), dictionary);
+    }
+
+    /// Render every value of kind `kind` with `renderer`, replacing
+    /// any renderer already registered for that kind.
+    pub fn register_renderer(&self, kind: ValueKind, renderer: impl AttributeRenderer + 'static) {
+        self.0
+            .borrow_mut()
+            .renderers
+            .0
+            .insert(kind, Rc::new(renderer));
+    }
+
+    pub fn renderer(&self, kind: ValueKind) -> Option<Rc<dyn AttributeRenderer>> {
+        RefCell::borrow(&*self.0).renderers.0.get(&kind).cloned()
+    }
+
+    /// Whether templates included by templates in this group can see
+    /// the attributes of the templates that included them.
+    pub fn has_dynamic_scoping(&self) -> bool {
+        RefCell::borrow(&*self.0).dynamic_scoping
+    }
+
+    /// Enable or disable dynamic scoping, which is on by default.
+    ///
+    /// With dynamic scoping disabled an included template can only
+    /// see the arguments passed to it.
+    pub fn set_dynamic_scoping(&self, enabled: bool) {
+        self.0.borrow_mut().dynamic_scoping = enabled;
     }
 }
 
@@ -169,16 +628,28 @@ impl Clone for Group {
 
 impl From<TemplateMap> for Group {
     fn from(templates: TemplateMap) -> Group {
-        Group(Rc::new(RefCell::new(templates)))
+        let templates = templates
+            .into_iter()
+            .map(|(name, template)| {
+                let template = template.named(&name);
+                (name, template)
+            })
+            .collect();
+        let imp = GroupImp {
+            templates,
+            ..GroupImp::default()
+        };
+        Group(Rc::new(RefCell::new(imp)))
     }
 }
 
 impl FromStr for Group {
     type Err = Error;
 
+    /// Parse a group, importing the groups it imports from paths
+    /// relative to the current directory.
     fn from_str(template: &str) -> Result<Group, Self::Err> {
-        let group = template.parse::<GroupBody>()?;
-        Ok(group.into())
+        loader::load_group(template, Path::new(""))
     }
 }
 
@@ -187,11 +658,15 @@ pub struct Template {
     pub group: Group,
     pub imp: CompiledTemplate,
     pub attributes: Attributes,
+    /// The group defining the template, which is one `group` imports
+    /// if the template was found there.
+    native_group: Group,
 }
 
 impl Template {
     pub fn new(group: Group, imp: CompiledTemplate) -> Template {
         Template {
+            native_group: group.clone(),
             group,
             imp,
             attributes: Attributes::new(),
@@ -204,16 +679,121 @@ impl Template {
         value: impl Serialize,
     ) -> Result<&mut Self, Error> {
         let name = name.into();
+        if name == interpreter::INDEX || name == interpreter::INDEX0 {
+            return Err(Error::ReservedAttribute(name));
+        }
         self.imp.assert_is_argument(&name)?;
         self.attributes.insert(name, Context::wraps(value)?);
         Ok(self)
     }
 
-    pub fn render(&self) -> String {
-        let template = self.imp.clone();
-        let group = self.group.clone();
-        let interpreter = Interpreter::new(group);
-        interpreter.render(&template, &self.attributes)
+    /// Add a value whose properties are read through its adaptor as
+    /// the template is rendered, instead of converting it to JSON.
+    pub fn add_model(
+        &mut self,
+        name: impl Into<String>,
+        model: impl ModelAdaptor + 'static,
+    ) -> Result<&mut Self, Error> {
+        let name = name.into();
+        if name == interpreter::INDEX || name == interpreter::INDEX0 {
+            return Err(Error::ReservedAttribute(name));
+        }
+        self.imp.assert_is_argument(&name)?;
+        self.attributes.insert(name, Context::model(model));
+        Ok(self)
+    }
+
+    pub fn render(&self) -> Result<String, Error> {
+        self.render_with(&self.interpreter())
+    }
+
+    /// Render the template, passing `locale` to the attribute
+    /// renderers of its group.
+    pub fn render_with_locale(&self, locale: &str) -> Result<String, Error> {
+        self.render_with(&self.interpreter().with_locale(locale))
+    }
+
+    /// Render the template, wrapping lines at `width` columns where
+    /// expressions have the `wrap` option.
+    pub fn render_with_line_width(&self, width: usize) -> Result<String, Error> {
+        self.render_with(&self.interpreter().with_line_width(width))
+    }
+
+    /// Render the template through writers created by `new_writer`,
+    /// such as `NoIndentWriter::boxed` to leave the output unindented.
+    pub fn render_with_writer(&self, new_writer: NewWriter) -> Result<String, Error> {
+        self.render_with(&self.interpreter().with_writer(new_writer))
+    }
+
+    /// Render the template to `out` as it's interpreted, without
+    /// collecting the whole output in memory first.
+    pub fn write_to<W: io::Write + ?Sized>(&self, out: &mut W) -> Result<(), Error> {
+        self.write_with(&self.interpreter(), out)
+    }
+
+    /// An interpreter for the group of the template, whose options can
+    /// be set before rendering with `render_with` or `write_with`, as
+    /// in `t.write_with(&t.interpreter().with_line_width(80), out)`.
+    pub fn interpreter(&self) -> Interpreter {
+        Interpreter::new(self.group.clone())
+    }
+
+    /// Render the template with the options of `interpreter`.
+    pub fn render_with(&self, interpreter: &Interpreter) -> Result<String, Error> {
+        let mut out = String::new();
+        self.write_fmt_with(interpreter, &mut out)?;
+        Ok(out)
+    }
+
+    /// Render the template to `out` with the options of `interpreter`,
+    /// as `write_to` does.
+    pub fn write_with<W: io::Write + ?Sized>(
+        &self,
+        interpreter: &Interpreter,
+        out: &mut W,
+    ) -> Result<(), Error> {
+        let mut writer = IoWriter {
+            inner: out,
+            error: None,
+        };
+        match self.write_fmt_with(interpreter, &mut writer) {
+            Err(Error::Fmt(_)) if writer.error.is_some() => Err(Error::Io(writer.error.unwrap())),
+            result => result,
+        }
+    }
+
+    fn write_fmt_with(
+        &self,
+        interpreter: &Interpreter,
+        out: &mut dyn fmt::Write,
+    ) -> Result<(), Error> {
+        interpreter.render_native(&self.imp, &self.attributes, &self.native_group, out)
+    }
+}
+
+/// Renders the template, failing with `fmt::Error` if rendering fails,
+/// which can't carry the error itself. Use `render` or `write_to` to
+/// find out what went wrong.
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_fmt_with(&self.interpreter(), f)
+            .map_err(|_| fmt::Error)
+    }
+}
+
+/// Adapts an `io::Write` to the `fmt::Write` the interpreter renders
+/// to, keeping hold of the I/O error that `fmt::Error` can't carry.
+struct IoWriter<'a, W: ?Sized> {
+    inner: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<'a, W: io::Write + ?Sized> fmt::Write for IoWriter<'a, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
     }
 }
 
@@ -225,7 +805,7 @@ impl Serialize for Template {
         use serde::ser::SerializeMap;
         let mut map = serializer.serialize_map(Some(self.attributes.len()))?;
         for (k, v) in &self.attributes {
-            map.serialize_entry(k, v.borrow())?;
+            map.serialize_entry(k, v)?;
         }
         map.end()
     }
@@ -233,10 +813,6 @@ impl Serialize for Template {
 
 impl From<CompiledTemplate> for Template {
     fn from(compiled: CompiledTemplate) -> Template {
-        Template {
-            group: Group::default(),
-            imp: compiled,
-            attributes: Attributes::new(),
-        }
+        Template::new(Group::default(), compiled)
     }
 }
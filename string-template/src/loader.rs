@@ -0,0 +1,91 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::parse::group::GroupParser;
+use crate::parse::pest::Delimiters;
+use crate::parse::Error as ParseError;
+use crate::{CompiledTemplate, Error, Group, Location};
+
+fn at(path: &Path, line: Option<usize>, error: Error) -> Error {
+    let location = Location {
+        path: path.to_owned(),
+        line,
+    };
+    Error::Load(location, Box::new(error))
+}
+
+fn parse_error_at(path: &Path, error: ParseError) -> Error {
+    at(path, error.line(), error.into())
+}
+
+/// Import the groups at `imports`, relative to `dir`, into `group`.
+///
+/// `loading` holds the canonical paths of the group files being loaded,
+/// which importing again would never finish.
+fn import_all(
+    group: &Group,
+    imports: Vec<String>,
+    dir: &Path,
+    loading: &mut Vec<PathBuf>,
+) -> Result<(), Error> {
+    for import in imports {
+        let path = dir.join(import);
+        let imported = if path.is_dir() {
+            Group::from_dir(&path)?
+        } else {
+            load_group_file_within(&path, loading)?
+        };
+        group.import(&imported)?;
+    }
+    Ok(())
+}
+
+/// Load the group of templates defined by `text`, importing the groups
+/// it imports from paths relative to `dir`.
+pub(crate) fn load_group(text: &str, dir: &Path) -> Result<Group, Error> {
+    let (group, imports) = GroupParser::group_of(text, Delimiters::default())?;
+    import_all(&group, imports, dir, &mut vec![])?;
+    Ok(group)
+}
+
+/// Load the group of templates in the file at `path`.
+pub(crate) fn load_group_file(path: &Path) -> Result<Group, Error> {
+    load_group_file_within(path, &mut vec![])
+}
+
+fn load_group_file_within(path: &Path, loading: &mut Vec<PathBuf>) -> Result<Group, Error> {
+    let canonical = fs::canonicalize(path).map_err(|error| at(path, None, error.into()))?;
+    if loading.contains(&canonical) {
+        return Err(at(path, None, Error::CircularImport));
+    }
+    let text = fs::read_to_string(path).map_err(|error| at(path, None, error.into()))?;
+    let (group, imports) = GroupParser::group_of(&text, Delimiters::default())
+        .map_err(|error| parse_error_at(path, error))?;
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    loading.push(canonical);
+    let imported = import_all(&group, imports, dir, loading);
+    loading.pop();
+    imported.map_err(|error| at(path, None, error))?;
+    Ok(group)
+}
+
+/// Load the template in the file at `path`, which defines just that
+/// template, as in `name(args) ::= <<...>>`, parsing it with
+/// `delimiters`.
+pub(crate) fn load_template_file(
+    path: &Path,
+    delimiters: Delimiters,
+) -> Result<CompiledTemplate, Error> {
+    let text = fs::read_to_string(path).map_err(|error| at(path, None, error.into()))?;
+    let (group, _) =
+        GroupParser::group_of(&text, delimiters).map_err(|error| parse_error_at(path, error))?;
+    let templates = &RefCell::borrow(&*group.0).templates;
+    match templates.values().next() {
+        Some(template) if templates.len() == 1 => Ok(template.clone()),
+        _ => {
+            let message = "expected a single template".to_string();
+            Err(at(path, None, ParseError::Formatted(message).into()))
+        }
+    }
+}
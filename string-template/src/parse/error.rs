@@ -3,13 +3,14 @@ use std::io::{Cursor, Write};
 
 use failure::Fail;
 
-use pest::error::Error as PestError;
+use pest::error::{Error as PestError, LineColLocation};
 
 use proc_macro2::Span;
 
 #[cfg(procmacro2_semver_exempt)]
 use proc_macro2::LineColumn;
 
+use crate::parse::group::Rule as GroupRule;
 use crate::parse::pest::Rule;
 
 #[cfg(procmacro2_semver_exempt)]
@@ -125,9 +126,35 @@ pub enum Error {
 
     #[fail(display = "{}", _0)]
     Syn(syn::Error),
+
+    #[fail(display = "{}", _0)]
+    Group(PestError<GroupRule>),
+
+    /// An error in a template of a group, which starts on the given
+    /// line of the group.
+    #[fail(display = "in template starting on line {}: {}", _0, _1)]
+    InTemplate(usize, Box<Error>),
+}
+
+fn line_of<R>(error: &PestError<R>) -> usize {
+    match error.line_col {
+        LineColLocation::Pos((line, _)) | LineColLocation::Span((line, _), _) => line,
+    }
 }
 
 impl Error {
+    /// The line of the parsed text the error is on, if it's known.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Error::Pest(error) => Some(line_of(error)),
+            Error::Group(error) => Some(line_of(error)),
+            Error::InTemplate(start, error) => {
+                Some(error.line().map_or(*start, |line| start + line - 1))
+            }
+            Error::Formatted(_) | Error::Syn(_) => None,
+        }
+    }
+
     pub fn syn(template: impl AsRef<str>, error: syn::Error) -> Error {
         make_error(template, error)
     }
@@ -150,6 +177,8 @@ impl From<Error> for syn::Error {
         match error {
             Error::Syn(error) => error,
             Error::Pest(error) => syn::Error::new(Span::call_site(), error),
+            Error::Group(error) => syn::Error::new(Span::call_site(), error),
+            Error::InTemplate(..) => syn::Error::new(Span::call_site(), error),
             Error::Formatted(error) => panic!("{}", error),
         }
     }
@@ -0,0 +1,268 @@
+use pest::error::{Error as PestError, ErrorVariant};
+use pest::iterators::Pair;
+use pest::Parser;
+
+use pest_derive::Parser;
+
+use crate::parse::pest::{Delimiters, TemplateParser};
+use crate::parse::Error;
+use crate::{CompiledTemplate, Dictionary, DictionaryDefault, Expr, Group};
+
+/// Unescape the content of a string in a group, keeping escapes the
+/// template itself understands.
+fn unescape(string: &str) -> String {
+    let mut unescaped = String::with_capacity(string.len());
+    let mut chars = string.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some(escaped @ ('"' | '\\'))) => unescaped.push(escaped),
+            ('\\', Some('n')) => unescaped.push('\n'),
+            ('\\', Some('r')) => unescaped.push('\r'),
+            ('\\', Some('t')) => unescaped.push('\t'),
+            (c, _) => {
+                unescaped.push(c);
+                continue;
+            }
+        }
+        chars.next();
+    }
+    unescaped
+}
+
+/// Unescape the content of a string that's the body of a template.
+///
+/// Within expressions, only quotes and backslashes are unescaped, so
+/// that special characters like `<\n>` reach the template parser, as
+/// do escaped start `delimiters`.
+fn unescape_template(string: &str, delimiters: Delimiters) -> String {
+    let mut unescaped = String::with_capacity(string.len());
+    let mut in_expression = false;
+    let mut chars = string.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some(escaped @ ('"' | '\\'))) => unescaped.push(escaped),
+            ('\\', Some(escaped)) if escaped == delimiters.start => {
+                unescaped.push('\\');
+                unescaped.push(escaped);
+            }
+            ('\\', Some(escaped)) if in_expression => {
+                unescaped.push('\\');
+                unescaped.push(escaped);
+            }
+            ('\\', Some('n')) => unescaped.push('\n'),
+            ('\\', Some('r')) => unescaped.push('\r'),
+            ('\\', Some('t')) => unescaped.push('\t'),
+            (c, _) => {
+                in_expression = if in_expression {
+                    c != delimiters.stop
+                } else {
+                    c == delimiters.start
+                };
+                unescaped.push(c);
+                continue;
+            }
+        }
+        chars.next();
+    }
+    unescaped
+}
+
+/// The line in the group that `pair` starts on.
+fn line_of(pair: &Pair<Rule>) -> usize {
+    pair.as_span().start_pos().line_col().0
+}
+
+/// Parse the text of a template, whose errors are reported relative
+/// to `line`, the line of the group it starts on.
+fn parse_template_text(
+    text: String,
+    line: usize,
+    delimiters: Delimiters,
+) -> Result<(String, Vec<Expr>), Error> {
+    match TemplateParser::expressions_of(&text, delimiters) {
+        Ok(expressions) => Ok((text, expressions)),
+        Err(error) => Err(Error::InTemplate(line, Box::new(error))),
+    }
+}
+
+fn parse_template_body(
+    body: Pair<Rule>,
+    delimiters: Delimiters,
+) -> Result<(String, Vec<Expr>), Error> {
+    let rule = body.as_rule();
+    let content = body.into_inner().next().unwrap();
+    let line = line_of(&content);
+    let text = match rule {
+        Rule::string => unescape_template(content.as_str(), delimiters),
+        Rule::big_string => content.as_str().to_string(),
+        Rule::big_string_no_newline => {
+            let mut text = String::new();
+            for (index, line) in content.as_str().lines().enumerate() {
+                let line = if index == 0 {
+                    line
+                } else {
+                    line.trim_start_matches([' ', '\t'])
+                };
+                text.push_str(line);
+            }
+            text
+        }
+        rule => unreachable!("unexpected rule: {:?}", rule),
+    };
+    parse_template_text(text, line, delimiters)
+}
+
+fn parse_value(value: Pair<Rule>, delimiters: Delimiters) -> Result<Expr, Error> {
+    match value.as_rule() {
+        Rule::string => {
+            let content = value.into_inner().next().unwrap();
+            Ok(Expr::Literal(unescape(content.as_str())))
+        }
+        Rule::boolean => Ok(Expr::Bool(value.as_str() == "true")),
+        Rule::list => {
+            let items = value.into_inner().map(|item| parse_value(item, delimiters));
+            Ok(Expr::List(items.collect::<Result<_, Error>>()?))
+        }
+        Rule::anonymous_template => {
+            let body = value.into_inner().next().unwrap();
+            let (line, text) = match body.as_rule() {
+                Rule::string => {
+                    let content = body.into_inner().next().unwrap();
+                    (
+                        line_of(&content),
+                        unescape_template(content.as_str(), delimiters),
+                    )
+                }
+                _ => (line_of(&body), body.as_str().to_string()),
+            };
+            let (_, expressions) = parse_template_text(text, line, delimiters)?;
+            Ok(Expr::SubTemplate(vec![], expressions))
+        }
+        rule => unreachable!("unexpected rule: {:?}", rule),
+    }
+}
+
+fn parse_template(
+    template: Pair<Rule>,
+    delimiters: Delimiters,
+) -> Result<(String, CompiledTemplate), Error> {
+    let mut content = template.into_inner();
+    let name = content.next().unwrap().as_str().to_string();
+    let mut formal_arguments = vec![];
+    for part in content {
+        match part.as_rule() {
+            Rule::formal_arg => {
+                let mut formal_arg = part.into_inner();
+                let name = formal_arg.next().unwrap().as_str().to_string();
+                let default = formal_arg
+                    .next()
+                    .map(|value| parse_value(value, delimiters))
+                    .transpose()?;
+                formal_arguments.push((name, default));
+            }
+            _ => {
+                let (text, expressions) = parse_template_body(part, delimiters)?;
+                let template = CompiledTemplate::with_args(text, formal_arguments, expressions);
+                return Ok((name, template));
+            }
+        }
+    }
+    unreachable!("template without a body")
+}
+
+fn parse_dictionary(
+    dictionary: Pair<Rule>,
+    delimiters: Delimiters,
+) -> Result<(String, Dictionary), Error> {
+    let mut content = dictionary.into_inner();
+    let name = content.next().unwrap().as_str().to_string();
+    let mut dictionary = Dictionary::new();
+    for entry in content {
+        let mut entry = entry.into_inner();
+        let key = entry.next().unwrap();
+        let key = match key.as_rule() {
+            Rule::string => Some(unescape(key.into_inner().next().unwrap().as_str())),
+            _ => None,
+        };
+        let value = entry.next().unwrap();
+        let value = match value.as_rule() {
+            Rule::key => None,
+            _ => Some(parse_value(value, delimiters)?),
+        };
+        match (key, value) {
+            (None, None) => dictionary.set_default(DictionaryDefault::Key),
+            (None, Some(value)) => dictionary.set_default(DictionaryDefault::Value(value)),
+            (Some(key), None) => dictionary.insert(key.clone(), Expr::Literal(key)),
+            (Some(key), Some(value)) => dictionary.insert(key, value),
+        }
+    }
+    Ok((name, dictionary))
+}
+
+fn parse_delimiters(definition: Pair<Rule>) -> Result<Delimiters, Error> {
+    let mut delimiters = definition.into_inner().map(|string| {
+        let content = string.into_inner().next().unwrap();
+        let delimiter = unescape(content.as_str());
+        let mut chars = delimiter.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => {
+                let variant = ErrorVariant::CustomError {
+                    message: "expected a single character".to_string(),
+                };
+                Err(Error::Group(PestError::new_from_span(
+                    variant,
+                    content.as_span(),
+                )))
+            }
+        }
+    });
+    let start = delimiters.next().unwrap()?;
+    let stop = delimiters.next().unwrap()?;
+    Ok(Delimiters::new(start, stop))
+}
+
+/// Parses groups at runtime, with template bodies written between
+/// double quotes, `<<` and `>>`, or `<%` and `%>`.
+#[derive(Parser)]
+#[grammar = "group.pest"]
+pub struct GroupParser;
+
+impl GroupParser {
+    /// The group defined by `group`, along with the paths of the
+    /// groups it imports, which aren't loaded.
+    ///
+    /// Templates are parsed with `delimiters`, unless the group
+    /// declares its own, as in `delimiters "$", "$"`.
+    pub fn group_of(
+        group: &str,
+        mut delimiters: Delimiters,
+    ) -> Result<(Group, Vec<String>), Error> {
+        let mut pairs = GroupParser::parse(Rule::group, group).map_err(Error::Group)?;
+        let mut templates = crate::TemplateMap::new();
+        let mut dictionaries = vec![];
+        let mut imports = vec![];
+        for definition in pairs.next().unwrap().into_inner() {
+            match definition.as_rule() {
+                Rule::delimiters => delimiters = parse_delimiters(definition)?,
+                Rule::import => {
+                    let path = definition.into_inner().next().unwrap();
+                    imports.push(unescape(path.into_inner().next().unwrap().as_str()));
+                }
+                Rule::template => {
+                    let (name, template) = parse_template(definition, delimiters)?;
+                    templates.insert(name, template);
+                }
+                Rule::dictionary => dictionaries.push(parse_dictionary(definition, delimiters)?),
+                Rule::EOI => {}
+                rule => unreachable!("unexpected rule: {:?}", rule),
+            }
+        }
+        let group = Group::from(templates);
+        group.set_delimiters(delimiters);
+        for (name, dictionary) in dictionaries {
+            group.add_dictionary(name, dictionary);
+        }
+        Ok((group, imports))
+    }
+}
@@ -1,24 +1,67 @@
+use pest::error::{Error as PestError, ErrorVariant, InputLocation};
 use pest::iterators::{Pair, Pairs};
-use pest::Parser;
+use pest::{Parser, Position, Span};
 
 use pest_derive::Parser;
 
 use crate::parse::Error;
-use crate::Expr;
+use crate::{Arguments, Expr, ExprOption, Function};
+
+fn custom_error(span: Span, message: impl Into<String>) -> Error {
+    let variant = ErrorVariant::CustomError {
+        message: message.into(),
+    };
+    PestError::new_from_span(variant, span).into()
+}
+
+fn unescape(string: &str) -> String {
+    let mut unescaped = String::with_capacity(string.len());
+    let mut chars = string.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') => unescaped.push('\n'),
+                Some('r') => unescaped.push('\r'),
+                Some('t') => unescaped.push('\t'),
+                Some(c) => unescaped.push(c),
+                None => unescaped.push(c),
+            },
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
 
 fn parse_field_reference(mut exprs: Pairs<Rule>) -> Result<Expr, Error> {
     let name = exprs.next().unwrap().as_str().to_string();
-    let path: Vec<String> = exprs
-        .map(|expr| match expr.as_rule() {
-            Rule::identifier => expr.as_str().to_string(),
+    let mut path = vec![];
+    let mut indirect = None;
+    for expr in exprs {
+        // Properties following an indirect property are looked up in
+        // its value.
+        let property = match expr.as_rule() {
+            Rule::identifier if indirect.is_none() => {
+                path.push(expr.as_str().to_string());
+                continue;
+            }
+            Rule::identifier => Expr::Literal(expr.as_str().to_string()),
+            Rule::indirect_property => parse_expr(expr.into_inner().next().unwrap())?,
             rule => unreachable!("unexpected rule: {:?}", rule),
-        })
-        .collect();
+        };
+        let object = match indirect.take() {
+            Some(object) => object,
+            None => attribute_path(name.clone(), std::mem::take(&mut path)),
+        };
+        indirect = Some(Expr::IndirectProperty(Box::new(object), Box::new(property)));
+    }
+    Ok(indirect.unwrap_or_else(|| attribute_path(name, path)))
+}
 
+fn attribute_path(name: String, path: Vec<String>) -> Expr {
     if path.is_empty() {
-        Ok(Expr::Attribute(name))
+        Expr::Attribute(name)
     } else {
-        Ok(Expr::AttributePath(name, path))
+        Expr::AttributePath(name, path)
     }
 }
 
@@ -27,37 +70,451 @@ fn parse_expr(expr: Pair<Rule>) -> Result<Expr, Error> {
         Rule::field_reference => parse_field_reference(expr.into_inner()),
         Rule::template_include => {
             let mut content = expr.into_inner();
-            let literal = content.next().unwrap().as_str();
-            Ok(Expr::Include(literal.to_string(), vec![]))
+            let name = content.next().unwrap();
+            let arguments = match content.next() {
+                Some(arguments) => parse_arguments(arguments)?,
+                None => Arguments::default(),
+            };
+            match name.as_rule() {
+                Rule::indirect_template_name => {
+                    let name = parse_expr(name.into_inner().next().unwrap())?;
+                    Ok(Expr::IndirectInclude(Box::new(name), arguments))
+                }
+                _ => Ok(Expr::Include(name.as_str().to_string(), arguments)),
+            }
+        }
+        Rule::function_call => {
+            let mut content = expr.into_inner();
+            let function = match content.next().unwrap().as_str() {
+                "first" => Function::First,
+                "last" => Function::Last,
+                "rest" => Function::Rest,
+                "trunc" => Function::Trunc,
+                "length" => Function::Length,
+                "reverse" => Function::Reverse,
+                "strip" => Function::Strip,
+                "trim" => Function::Trim,
+                "strlen" => Function::Strlen,
+                name => unreachable!("unexpected function: {}", name),
+            };
+            let argument = parse_expr(content.next().unwrap())?;
+            Ok(Expr::Function(function, Box::new(argument)))
+        }
+        Rule::map_expression | Rule::argument_map_expression => {
+            let mut content = expr.into_inner();
+            let value = parse_expr(content.next().unwrap())?;
+            content.try_fold(value, parse_map)
+        }
+        Rule::parallel_map_expression => {
+            let span = expr.as_span();
+            let mut content = expr.into_inner();
+            let values = content.next().unwrap().into_inner().map(parse_expr);
+            let values = values.collect::<Result<Vec<_>, Error>>()?;
+            let template = parse_expr(content.next().unwrap())?;
+            match &template {
+                Expr::SubTemplate(parameters, _) if parameters.len() == values.len() => {}
+                _ => {
+                    let message = format!(
+                        "iterating through {0} attributes in parallel needs \
+                         an anonymous template with {0} parameters",
+                        values.len()
+                    );
+                    return Err(custom_error(span, message));
+                }
+            }
+            let value = Expr::ParallelMap(values, Box::new(template));
+            content.try_fold(value, parse_map)
+        }
+        Rule::anonymous_template => {
+            let mut parameters = vec![];
+            let mut body = vec![];
+            for part in expr.into_inner() {
+                match part.as_rule() {
+                    Rule::template_parameters => {
+                        parameters = part
+                            .into_inner()
+                            .map(|parameter| parameter.as_str().to_string())
+                            .collect();
+                    }
+                    Rule::template_body => body = parse_template_body(part, false)?,
+                    rule => unreachable!("unexpected rule: {:?}", rule),
+                }
+            }
+            Ok(Expr::SubTemplate(parameters, body))
+        }
+        Rule::string_literal => {
+            let content = expr.into_inner().next().unwrap();
+            Ok(Expr::String(unescape(content.as_str())))
+        }
+        Rule::list_literal => {
+            let items = expr.into_inner().map(parse_expr);
+            Ok(Expr::List(items.collect::<Result<_, _>>()?))
         }
         rule => unimplemented!("{:?}", rule),
     }
 }
 
+/// Parse a template applied to `value`, or templates applied in turn.
+fn parse_map(value: Expr, templates: Pair<Rule>) -> Result<Expr, Error> {
+    match templates.as_rule() {
+        Rule::round_robin_templates => {
+            let templates = templates.into_inner().map(parse_expr);
+            let templates = templates.collect::<Result<_, Error>>()?;
+            Ok(Expr::RoundRobinMap(Box::new(value), templates))
+        }
+        _ => Ok(Expr::Map(Box::new(value), Box::new(parse_expr(templates)?))),
+    }
+}
+
+fn parse_arguments(arguments: Pair<Rule>) -> Result<Arguments, Error> {
+    match arguments.as_rule() {
+        Rule::positional_arguments => {
+            let values = arguments.into_inner().map(parse_expr);
+            Ok(Arguments::Positional(values.collect::<Result<_, Error>>()?))
+        }
+        Rule::named_arguments => {
+            let mut values = vec![];
+            let mut pass_through = false;
+            for argument in arguments.into_inner() {
+                match argument.as_rule() {
+                    Rule::named_argument => {
+                        let mut content = argument.into_inner();
+                        let name = content.next().unwrap().as_str().to_string();
+                        values.push((name, parse_expr(content.next().unwrap())?));
+                    }
+                    Rule::pass_through => pass_through = true,
+                    rule => unreachable!("unexpected rule: {:?}", rule),
+                }
+            }
+            if pass_through {
+                Ok(Arguments::PassThrough(values))
+            } else {
+                Ok(Arguments::Named(values))
+            }
+        }
+        rule => unreachable!("unexpected rule: {:?}", rule),
+    }
+}
+
+fn parse_option(option: Pair<Rule>) -> Result<(ExprOption, Expr), Error> {
+    let mut content = option.into_inner();
+    let name = content.next().unwrap();
+    let (option, default) = match name.as_str() {
+        "anchor" => (ExprOption::Anchor, Some("true")),
+        "format" => (ExprOption::Format, None),
+        "null" => (ExprOption::Null, None),
+        "separator" => (ExprOption::Separator, None),
+        "wrap" => (ExprOption::Wrap, Some("\n")),
+        unknown => {
+            let message = format!("no such option: {}", unknown);
+            return Err(custom_error(name.as_span(), message));
+        }
+    };
+    match (content.next(), default) {
+        (Some(value), _) => Ok((option, parse_expr(value)?)),
+        (None, Some(default)) => Ok((option, Expr::Literal(default.to_string()))),
+        (None, None) => {
+            let message = format!("value required for option: {}", name.as_str());
+            Err(custom_error(name.as_span(), message))
+        }
+    }
+}
+
+fn parse_binary_condition(
+    operands: Pairs<Rule>,
+    operator: fn(Box<Expr>, Box<Expr>) -> Expr,
+) -> Result<Expr, Error> {
+    let mut operands = operands.map(parse_condition);
+    let first = operands.next().unwrap()?;
+    operands.try_fold(first, |lhs, rhs| {
+        Ok(operator(Box::new(lhs), Box::new(rhs?)))
+    })
+}
+
+fn parse_condition(condition: Pair<Rule>) -> Result<Expr, Error> {
+    match condition.as_rule() {
+        Rule::condition => parse_binary_condition(condition.into_inner(), Expr::Or),
+        Rule::and_condition => parse_binary_condition(condition.into_inner(), Expr::And),
+        Rule::not_condition => {
+            let mut content = condition.into_inner();
+            let operand = content.next().unwrap();
+            match operand.as_rule() {
+                Rule::negation => {
+                    let negated = parse_condition(content.next().unwrap())?;
+                    Ok(Expr::Not(Box::new(negated)))
+                }
+                _ => parse_condition(operand),
+            }
+        }
+        _ => parse_expr(condition),
+    }
+}
+
+/// Parse a conditional, which starts a line if `at_line_start`, and is
+/// followed by the literal text `following`.
+///
+/// Tags alone on their lines are dropped along with their indentation
+/// and newline, so the conditional is returned along with whether its
+/// first and last tags are.
+fn parse_conditional(
+    conditional: Pair<Rule>,
+    at_line_start: bool,
+    following: &str,
+) -> Result<(Expr, bool, bool), Error> {
+    let branches: Vec<_> = conditional.into_inner().collect();
+    let mut alone = at_line_start && starts_with_line_end(body_text(&branches[0]));
+    let first_alone = alone;
+    let mut conditions = vec![];
+    let mut bodies = vec![];
+    for (index, branch) in branches.iter().enumerate() {
+        let mut content = branch.clone().into_inner();
+        match branch.as_rule() {
+            Rule::if_branch | Rule::elseif_branch => {
+                conditions.push(parse_condition(content.next().unwrap())?);
+            }
+            Rule::else_branch => {}
+            rule => unreachable!("unexpected rule: {:?}", rule),
+        }
+        let body = content.next().unwrap();
+        let next_text = branches.get(index + 1).map_or(following, body_text);
+        let next_alone =
+            ends_at_line_start(body.as_str(), alone) && starts_with_line_end(next_text);
+        let mut expressions = parse_template_body(body, alone)?;
+        if let (true, Some(Expr::Literal(first))) = (alone, expressions.first_mut()) {
+            strip_line_end(first);
+        }
+        if let (true, Some(Expr::Literal(last))) = (next_alone, expressions.last_mut()) {
+            strip_indentation(last);
+        }
+        bodies.push(expressions);
+        alone = next_alone;
+    }
+
+    // `elseif` branches become conditionals nested in the
+    // alternative of the branch before them.
+    let mut alternative = if bodies.len() > conditions.len() {
+        bodies.pop().unwrap()
+    } else {
+        vec![]
+    };
+    while let (Some(condition), Some(consequent)) = (conditions.pop(), bodies.pop()) {
+        alternative = vec![Expr::If(Box::new(condition), consequent, alternative)];
+    }
+    Ok((alternative.pop().unwrap(), first_alone, alone))
+}
+
+/// The text of the body of a branch of a conditional.
+fn body_text<'i>(branch: &Pair<'i, Rule>) -> &'i str {
+    branch.clone().into_inner().last().unwrap().as_str()
+}
+
+/// Whether the end of `text` is at the start of a line, apart from
+/// indentation, where `at_line_start` tells whether `text` starts one.
+fn ends_at_line_start(text: &str, at_line_start: bool) -> bool {
+    let (line, starts_line) = match text.rfind('\n') {
+        Some(newline) => (&text[newline + 1..], true),
+        None => (text, at_line_start),
+    };
+    starts_line && line.chars().all(|c| c == ' ' || c == '\t')
+}
+
+/// Whether `text` starts with the end of a line, apart from spaces.
+fn starts_with_line_end(text: &str) -> bool {
+    let text = text.trim_start_matches([' ', '\t']);
+    text.starts_with('\n') || text.starts_with("\r\n")
+}
+
+fn strip_line_end(text: &mut String) {
+    let spaces = text.len() - text.trim_start_matches([' ', '\t']).len();
+    let newline = if text[spaces..].starts_with("\r\n") {
+        2
+    } else {
+        1
+    };
+    text.drain(..spaces + newline);
+}
+
+fn strip_indentation(text: &mut String) {
+    let len = text.trim_end_matches([' ', '\t']).len();
+    text.truncate(len);
+}
+
 fn parse_expression(expression: Pair<Rule>) -> Result<Expr, Error> {
     match expression.as_rule() {
         Rule::literal => {
-            let literal = expression.as_str();
-            Ok(Expr::Literal(literal.to_string()))
+            let start = expression.as_span().start();
+            let text = expression.as_str();
+            let mut literal = String::with_capacity(text.len());
+            let mut from = 0;
+            for escaped_delimiter in expression.into_inner() {
+                // Skip the backslash.
+                literal.push_str(&text[from..escaped_delimiter.as_span().start() - start]);
+                from = escaped_delimiter.as_span().start() - start + 1;
+            }
+            literal.push_str(&text[from..]);
+            Ok(Expr::Literal(literal))
+        }
+        Rule::special_characters => {
+            let mut literal = String::new();
+            for special_character in expression.into_inner() {
+                literal.push(parse_special_character(special_character)?);
+            }
+            Ok(Expr::Literal(literal))
+        }
+        Rule::expression => {
+            let mut content = expression.into_inner();
+            let expr = parse_expr(content.next().unwrap())?;
+            let options = content.map(parse_option).collect::<Result<Vec<_>, _>>()?;
+            if options.is_empty() {
+                Ok(expr)
+            } else {
+                Ok(Expr::Options(Box::new(expr), options))
+            }
+        }
+        Rule::region => {
+            let mut content = expression.into_inner();
+            let name = content.next().unwrap().as_str().to_string();
+            let mut default = match content.next() {
+                Some(body) => parse_template_body(body, false)?,
+                None => vec![],
+            };
+            // The newlines just inside the tags of an embedded region
+            // aren't part of it.
+            if let Some(Expr::Literal(first)) = default.first_mut() {
+                if first.starts_with('\n') {
+                    first.remove(0);
+                } else if first.starts_with("\r\n") {
+                    first.drain(..2);
+                }
+            }
+            if let Some(Expr::Literal(last)) = default.last_mut() {
+                if last.ends_with("\r\n") {
+                    last.truncate(last.len() - 2);
+                } else if last.ends_with('\n') {
+                    last.pop();
+                }
+            }
+            Ok(Expr::Region(name, default))
         }
-        Rule::expression => parse_expr(expression.into_inner().next().unwrap()),
         rule => unimplemented!("{:?}", rule),
     }
 }
 
+fn parse_special_character(special_character: Pair<Rule>) -> Result<char, Error> {
+    match &special_character.as_str()[1..] {
+        "n" => Ok('\n'),
+        "t" => Ok('\t'),
+        " " => Ok(' '),
+        unicode => u32::from_str_radix(&unicode[1..], 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| custom_error(special_character.as_span(), "invalid unicode character")),
+    }
+}
+
+/// Parse the expressions of a template body, joining up the literal
+/// text on either side of comments and special characters.
+///
+/// `at_line_start` tells whether the body starts a line, so that
+/// conditional tags alone on their lines can be dropped.
+fn parse_template_body(body: Pair<Rule>, at_line_start: bool) -> Result<Vec<Expr>, Error> {
+    let mut expressions: Vec<Expr> = vec![];
+    // Whether the literal text since the last expression starts a line.
+    let mut line_start = at_line_start;
+    let mut strip_newline = false;
+    let mut pairs = body.into_inner().peekable();
+    while let Some(expression) = pairs.next() {
+        if expression.as_rule() == Rule::conditional {
+            let at_line_start = match expressions.last() {
+                Some(Expr::Literal(text)) => ends_at_line_start(text, line_start),
+                Some(_) => false,
+                None => line_start,
+            };
+            let following = pairs.peek().map_or("", Pair::as_str);
+            let (conditional, first_alone, last_alone) =
+                parse_conditional(expression, at_line_start, following)?;
+            if let (true, Some(Expr::Literal(last))) = (first_alone, expressions.last_mut()) {
+                strip_indentation(last);
+            }
+            expressions.push(conditional);
+            line_start = last_alone;
+            strip_newline = last_alone;
+            continue;
+        }
+        match (expressions.last_mut(), parse_expression(expression)?) {
+            (Some(Expr::Literal(last)), Expr::Literal(literal)) => last.push_str(&literal),
+            (_, Expr::Literal(mut literal)) => {
+                if strip_newline {
+                    strip_line_end(&mut literal);
+                }
+                expressions.push(Expr::Literal(literal));
+            }
+            (_, expression) => {
+                line_start = false;
+                expressions.push(expression);
+            }
+        }
+        strip_newline = false;
+    }
+    Ok(expressions)
+}
+
+/// The characters expressions start and stop with, which are `<` and
+/// `>` by default.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Delimiters {
+    pub start: char,
+    pub stop: char,
+}
+
+impl Delimiters {
+    pub fn new(start: char, stop: char) -> Delimiters {
+        Delimiters { start, stop }
+    }
+}
+
+impl Default for Delimiters {
+    fn default() -> Delimiters {
+        Delimiters::new('<', '>')
+    }
+}
+
+/// Move an error in `template` following a header of `header_len`
+/// bytes so it's relative to the template alone.
+fn without_header(error: Error, template: &str, header_len: usize) -> Error {
+    let error = match error {
+        Error::Pest(error) => error,
+        error => return error,
+    };
+    let error = match error.location {
+        InputLocation::Pos(pos) => {
+            let pos = Position::new(template, pos.saturating_sub(header_len)).unwrap();
+            PestError::new_from_pos(error.variant, pos)
+        }
+        InputLocation::Span((start, end)) => {
+            let start = start.saturating_sub(header_len);
+            let end = end.saturating_sub(header_len);
+            PestError::new_from_span(error.variant, Span::new(template, start, end).unwrap())
+        }
+    };
+    Error::Pest(error)
+}
+
 #[derive(Copy, Clone, Debug, Parser)]
 #[grammar = "template.pest"]
 pub struct TemplateParser;
 
 impl TemplateParser {
-    pub fn expressions_of(template: &str) -> Result<Vec<Expr>, Error> {
-        let mut pairs = TemplateParser::parse(Rule::template_body, template)?;
-        pairs
-            .next()
-            .unwrap()
-            .into_inner()
-            .map(parse_expression)
-            .collect()
+    pub fn expressions_of(template: &str, delimiters: Delimiters) -> Result<Vec<Expr>, Error> {
+        // The grammar reads the delimiters from the start of the input.
+        let input = format!("{}{}{}", delimiters.start, delimiters.stop, template);
+        let header_len = input.len() - template.len();
+        let parse = || {
+            let mut pairs = TemplateParser::parse(Rule::template, &input)?;
+            let body = pairs.next().unwrap().into_inner().next().unwrap();
+            parse_template_body(body, true)
+        };
+        parse().map_err(|error| without_header(error, template, header_len))
     }
 }
 
@@ -69,34 +526,166 @@ mod tests {
 
     #[test]
     fn parse_structure() {
+        // The input starts with the delimiters.
         parses_to! {
             parser: TemplateParser,
-            input: r#"<greeting> <person.name>! <message()>"#,
-            rule: Rule::template_body,
+            input: r#"<><greeting> <person.name>! <message()>"#,
+            rule: Rule::template,
             tokens: [
-                template_body(0, 37, [
-                    literal(0, 0),
-                    expression(0, 10, [
-                        field_reference(1, 9, [
-                            identifier(1, 9)
-                        ])
-                    ]),
-                    literal(10, 11),
-                    expression(11, 24, [
-                        field_reference(12, 23, [
-                            identifier(12, 18),
-                            identifier(19, 23)
-                        ])
+                template(0, 39, [
+                    template_body(2, 39, [
+                        literal(2, 2),
+                        expression(2, 12, [
+                            field_reference(3, 11, [
+                                identifier(3, 11)
+                            ])
+                        ]),
+                        literal(12, 13),
+                        expression(13, 26, [
+                            field_reference(14, 25, [
+                                identifier(14, 20),
+                                identifier(21, 25)
+                            ])
+                        ]),
+                        literal(26, 28),
+                        expression(28, 39, [
+                            template_include(29, 38, [
+                                identifier(29, 36)
+                            ])
+                        ]),
+                        literal(39, 39)
                     ]),
-                    literal(24, 26),
-                    expression(26, 37, [
-                        template_include(27, 36, [
-                            identifier(27, 34)
-                        ])
-                    ]),
-                    literal(37, 37)
+                    EOI(39, 39)
                 ])
             ]
         }
     }
+
+    #[test]
+    fn parse_else_if_as_nested_conditional() {
+        let attribute = |name: &str| Box::new(Expr::Attribute(name.into()));
+        let literal = |text: &str| Expr::Literal(text.into());
+        assert_eq!(
+            vec![
+                literal(""),
+                Expr::If(
+                    Box::new(Expr::Not(attribute("a"))),
+                    vec![literal("A")],
+                    vec![Expr::If(
+                        Box::new(Expr::Or(
+                            attribute("b"),
+                            Box::new(Expr::And(attribute("c"), attribute("d")))
+                        )),
+                        vec![literal("B")],
+                        vec![literal("C")]
+                    )]
+                ),
+                literal("")
+            ],
+            TemplateParser::expressions_of(
+                "<if(!a)>A<elseif(b || c && d)>B<else>C<endif>",
+                Delimiters::default()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_include_of_qualified_template_name() {
+        let include = |name: &str| Expr::Include(name.into(), Arguments::default());
+        let literal = |s: &str| Expr::Literal(s.into());
+        assert_eq!(
+            vec![
+                literal(""),
+                include("/sub/t"),
+                literal(" "),
+                include("sub/t"),
+                literal("")
+            ],
+            TemplateParser::expressions_of("</sub/t()> <sub/t()>", Delimiters::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_include_of_super_template() {
+        assert_eq!(
+            vec![
+                Expr::Literal("".into()),
+                Expr::Include("super.t".into(), Arguments::default()),
+                Expr::Literal("".into())
+            ],
+            TemplateParser::expressions_of("<super.t()>", Delimiters::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_comments_and_special_characters_as_literal_text() {
+        assert_eq!(
+            vec![
+                Expr::Literal("a\n<b ".into()),
+                Expr::Attribute("x".into()),
+                Expr::Literal("".into())
+            ],
+            TemplateParser::expressions_of(
+                r"a<! comment !><\n>\<b<! <x> !> <x>",
+                Delimiters::default()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_with_custom_delimiters() {
+        assert_eq!(
+            vec![
+                Expr::Literal("a < b ".into()),
+                Expr::Attribute("x".into()),
+                Expr::Literal(" $".into())
+            ],
+            TemplateParser::expressions_of(r"a < b $x$ \$", Delimiters::new('$', '$')).unwrap()
+        );
+    }
+
+    #[test]
+    fn report_errors_relative_to_template() {
+        let error = TemplateParser::expressions_of("ok\n<x;;>", Delimiters::default()).unwrap_err();
+        assert_eq!(Some(2), error.line());
+    }
+
+    #[test]
+    fn parse_indirect_property_and_include() {
+        let attribute = |name: &str| Box::new(Expr::Attribute(name.into()));
+        assert_eq!(
+            vec![
+                Expr::Literal("".into()),
+                Expr::IndirectProperty(
+                    Box::new(Expr::IndirectProperty(
+                        Box::new(Expr::AttributePath("a".into(), vec!["b".into()])),
+                        attribute("c")
+                    )),
+                    Box::new(Expr::Literal("d".into()))
+                ),
+                Expr::Literal("".into()),
+                Expr::IndirectInclude(attribute("t"), Arguments::Positional(vec![*attribute("x")])),
+                Expr::Literal("".into())
+            ],
+            TemplateParser::expressions_of("<a.b.(c).d><(t)(x)>", Delimiters::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_regions() {
+        let literal = |s: &str| Expr::Literal(s.into());
+        assert_eq!(
+            vec![
+                literal(""),
+                Expr::Region("r".into(), vec![literal("default")]),
+                literal(""),
+                Expr::Region("s".into(), vec![]),
+                literal("")
+            ],
+            TemplateParser::expressions_of("<@r>\ndefault\n<@end><@s()>", Delimiters::default())
+                .unwrap()
+        );
+    }
 }
@@ -1,19 +1,24 @@
-use std::{cmp, fmt, str};
+use std::cell::RefCell;
+use std::path::Path;
+use std::{cmp, env, fmt, fs, str};
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
 
 use quote::ToTokens;
 use quote::{quote, quote_spanned};
 
-use syn::parse::{Parse, ParseStream};
+use syn::parse::{Parse, ParseStream, Parser};
 use syn::punctuated::Punctuated;
-use syn::{braced, parenthesized, token, Ident, Token, Visibility};
+use syn::{braced, bracketed, parenthesized, token, Ident, LitStr, Token, Visibility};
 
-use crate::parse::pest::TemplateParser;
+use crate::parse::pest::{Delimiters, TemplateParser};
 use crate::parse::Error;
-use crate::{CompiledTemplate, Expr, Group as RuntimeGroup, TemplateMap};
+use crate::{
+    Arguments, CompiledTemplate, Dictionary as RuntimeDictionary, DictionaryDefault, Expr,
+    ExprOption, Function, Group as RuntimeGroup, TemplateMap,
+};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 struct NoneDelimiter;
 
 impl Parse for NoneDelimiter {
@@ -22,6 +27,24 @@ impl Parse for NoneDelimiter {
     }
 }
 
+/// Parse the comma separated values of `input` with `parse`, which
+/// takes the delimiters of templates.
+fn parse_terminated<T>(
+    input: ParseStream,
+    delimiters: Delimiters,
+    parse: fn(ParseStream, Delimiters) -> syn::Result<T>,
+) -> syn::Result<Punctuated<T, Token![,]>> {
+    let mut punctuated = Punctuated::new();
+    while !input.is_empty() {
+        punctuated.push_value(parse(input, delimiters)?);
+        if input.is_empty() {
+            break;
+        }
+        punctuated.push_punct(input.parse()?);
+    }
+    Ok(punctuated)
+}
+
 #[derive(Clone)]
 pub struct Group {
     visibility: Visibility,
@@ -90,6 +113,9 @@ impl ToTokens for Group {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let ty = quote! { ::string_template::Group };
         let templates = &self.group;
+        let dictionaries = self.group.dictionary_definitions();
+        let delimiters = self.group.delimiter_definitions();
+        let imports = self.group.import_definitions();
         let visibility = &self.visibility;
         let template_access_fns = self.group.template_access_fns();
         let group_name = &self.group_name;
@@ -112,7 +138,11 @@ impl ToTokens for Group {
                         fn init() -> #ty {
                             let mut templates = ::std::collections::HashMap::new();
                             #templates
-                            ::string_template::Group::from(templates)
+                            let group = ::string_template::Group::from(templates);
+                            #delimiters
+                            #dictionaries
+                            #imports
+                            group
                         }
 
                         unsafe {
@@ -129,25 +159,245 @@ impl ToTokens for Group {
 #[derive(Clone, Debug, PartialEq)]
 pub struct GroupBody {
     visibility: Visibility,
+    delimiters: Delimiters,
+    imports: Vec<Import>,
     templates: Punctuated<Template, NoneDelimiter>,
+    dictionaries: Vec<Dictionary>,
+}
+
+/// A group imported by a group, as in `import "base.stg"`, which is
+/// loaded while parsing the group so the macro can embed it.
+#[derive(Clone, Debug, PartialEq)]
+struct Import {
+    path: LitStr,
+    group: RuntimeGroup,
+}
+
+impl Import {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path: LitStr = input.parse()?;
+        let dir = env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+        let group = load_import(&Path::new(&dir).join(path.value())).map_err(|error| {
+            syn::Error::new(
+                path.span(),
+                format!("failed to import {}: {}", path.value(), error),
+            )
+        })?;
+        Ok(Import { path, group })
+    }
+}
+
+/// Load the group file or template directory at `path`, along with
+/// every template in the directories it and its imports are loaded
+/// from.
+fn load_import(path: &Path) -> Result<RuntimeGroup, crate::Error> {
+    let group = if path.is_dir() {
+        RuntimeGroup::from_dir(path)?
+    } else {
+        RuntimeGroup::from_file(path)?
+    };
+    load_templates(&group)?;
+    Ok(group)
+}
+
+fn load_templates(group: &RuntimeGroup) -> Result<(), crate::Error> {
+    let (root, imports) = {
+        let imp = RefCell::borrow(&*group.0);
+        (imp.root.clone(), imp.imports.clone())
+    };
+    if let Some(root) = root {
+        load_template_files(group, &root, "")?;
+    }
+    for import in &imports {
+        load_templates(import)?;
+    }
+    Ok(())
+}
+
+/// Load the templates in the `.st` files in `dir`, which is the
+/// directory `prefix` of the group.
+fn load_template_files(group: &RuntimeGroup, dir: &Path, prefix: &str) -> Result<(), crate::Error> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let stem = match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(stem) => format!("{}{}", prefix, stem),
+            None => continue,
+        };
+        if path.is_dir() {
+            load_template_files(group, &path, &format!("{}/", stem))?;
+        } else if path.extension().and_then(|extension| extension.to_str()) == Some("st") {
+            group.load(&stem)?;
+        }
+    }
+    Ok(())
+}
+
+impl ToTokens for RuntimeGroup {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let imp = RefCell::borrow(&*self.0);
+        let templates = imp.templates.iter().map(|(name, template)| {
+            quote! { templates.insert(#name.to_string(), #template); }
+        });
+        let Delimiters { start, stop } = imp.delimiters;
+        let dictionaries = imp.dictionaries.iter().map(|(name, dictionary)| {
+            quote! { group.add_dictionary(#name, #dictionary); }
+        });
+        let imports = &imp.imports;
+        tokens.extend(quote! {
+            {
+                #[allow(unused_mut)]
+                let mut templates = ::std::collections::HashMap::new();
+                #( #templates )*
+                let group = ::string_template::Group::from(templates);
+                group.set_delimiters(::string_template::Delimiters::new(#start, #stop));
+                #( #dictionaries )*
+                #( group.import(&#imports).unwrap(); )*
+                group
+            }
+        });
+    }
+}
+
+impl ToTokens for CompiledTemplate {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let template = &self.template;
+        let expressions = &self.expressions;
+        let expanded = match &self.formal_arguments {
+            Some(formal_arguments) => {
+                let formal_arguments =
+                    formal_arguments
+                        .iter()
+                        .map(|(name, default)| match default {
+                            Some(expr) => quote! { (#name.to_string(), Some(#expr)) },
+                            None => quote! { (#name.to_string(), None) },
+                        });
+                quote! {
+                    ::string_template::CompiledTemplate::with_args(
+                        #template,
+                        vec![ #( #formal_arguments ),* ],
+                        vec![ #( #expressions ),* ]
+                    )
+                }
+            }
+            None => quote! {
+                ::string_template::CompiledTemplate::new(#template, vec![ #( #expressions ),* ])
+            },
+        };
+        tokens.extend(expanded);
+    }
+}
+
+impl ToTokens for RuntimeDictionary {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let entries = self.entries.iter().map(|(key, value)| {
+            quote! { dictionary.insert(#key, #value); }
+        });
+        let default = match &self.default {
+            Some(DictionaryDefault::Key) => quote! {
+                dictionary.set_default(::string_template::DictionaryDefault::Key);
+            },
+            Some(DictionaryDefault::Value(value)) => quote! {
+                dictionary.set_default(::string_template::DictionaryDefault::Value(#value));
+            },
+            None => quote! {},
+        };
+        tokens.extend(quote! {
+            {
+                let mut dictionary = ::string_template::Dictionary::new();
+                #( #entries )*
+                #default
+                dictionary
+            }
+        });
+    }
 }
 
 impl GroupBody {
     pub fn new(visibility: Visibility, input: ParseStream) -> syn::Result<GroupBody> {
-        let templates = input.parse_terminated(Template::parse)?;
+        let delimiters = if input.peek(Ident)
+            && input.peek2(LitStr)
+            && input.fork().parse::<Ident>()? == "delimiters"
+        {
+            input.parse::<Ident>()?;
+            let start = parse_delimiter(input)?;
+            input.parse::<Token![,]>()?;
+            let stop = parse_delimiter(input)?;
+            Delimiters::new(start, stop)
+        } else {
+            Delimiters::default()
+        };
+        let mut imports = vec![];
+        let mut templates = Punctuated::new();
+        let mut dictionaries = vec![];
+        while !input.is_empty() {
+            if input.peek2(LitStr) {
+                let keyword: Ident = input.parse()?;
+                if keyword != "import" {
+                    return Err(syn::Error::new(keyword.span(), "expected `import`"));
+                }
+                imports.push(Import::parse(input)?);
+            } else if input.peek(Token![@]) || input.peek2(token::Paren) {
+                templates.push(Template::parse(input, delimiters)?);
+            } else {
+                dictionaries.push(Dictionary::parse(input, delimiters)?);
+            }
+        }
         Ok(GroupBody {
             visibility,
+            delimiters,
+            imports,
             templates,
+            dictionaries,
         })
     }
 
+    /// Statements setting the delimiters of `group`, if they aren't the
+    /// default.
+    pub fn delimiter_definitions(&self) -> TokenStream {
+        if self.delimiters == Delimiters::default() {
+            return quote! {};
+        }
+        let Delimiters { start, stop } = self.delimiters;
+        quote! {
+            group.set_delimiters(::string_template::Delimiters::new(#start, #stop));
+        }
+    }
+
+    /// Statements importing the groups imported by the group into
+    /// `group`, which were loaded from paths relative to the crate
+    /// being compiled when the group was parsed.
+    pub fn import_definitions(&self) -> TokenStream {
+        let imports = self.imports.iter().map(|Import { path, group }| {
+            // Rebuild the crate when an imported group file changes.
+            let tracked = if RefCell::borrow(&*group.0).root.is_none() {
+                quote_spanned! {
+                    path.span() =>
+                        let _ = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/", #path));
+                }
+            } else {
+                quote! {}
+            };
+            quote! {
+                #tracked
+                group.import(&#group).unwrap();
+            }
+        });
+        quote! { #( #imports )* }
+    }
+
     pub fn templates(self) -> TemplateMap {
         self.templates
             .into_iter()
-            .map(|st| (st.name.to_string(), st.into()))
+            .map(|st| (st.full_name(), st.into()))
             .collect()
     }
 
+    /// Statements adding the dictionaries of the group to `group`.
+    pub fn dictionary_definitions(&self) -> TokenStream {
+        let dictionaries = &self.dictionaries;
+        quote! { #( #dictionaries )* }
+    }
+
     pub fn template_access_fns(&self) -> TokenStream {
         let template_access_fns = self
             .templates
@@ -157,6 +407,19 @@ impl GroupBody {
     }
 }
 
+fn parse_delimiter(input: ParseStream) -> syn::Result<char> {
+    let literal: LitStr = input.parse()?;
+    let delimiter = literal.value();
+    let mut chars = delimiter.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(syn::Error::new(
+            literal.span(),
+            "expected a single character",
+        )),
+    }
+}
+
 fn public_visibility() -> Visibility {
     Visibility::Public(syn::VisPublic {
         pub_token: Default::default(),
@@ -167,15 +430,28 @@ impl Default for GroupBody {
     fn default() -> GroupBody {
         GroupBody {
             visibility: public_visibility(),
+            delimiters: Delimiters::default(),
+            imports: Default::default(),
             templates: Default::default(),
+            dictionaries: Default::default(),
         }
     }
 }
 
 impl From<GroupBody> for RuntimeGroup {
     fn from(static_group: GroupBody) -> RuntimeGroup {
-        let templates = static_group.templates();
-        RuntimeGroup::from(templates)
+        let dictionaries = static_group.dictionaries.clone();
+        let delimiters = static_group.delimiters;
+        let imports = static_group.imports.clone();
+        let group = RuntimeGroup::from(static_group.templates());
+        group.set_delimiters(delimiters);
+        for dictionary in dictionaries {
+            group.add_dictionary(dictionary.name.to_string(), dictionary.into());
+        }
+        for import in imports {
+            group.import(&import.group).unwrap();
+        }
+        group
     }
 }
 
@@ -204,14 +480,29 @@ impl ToTokens for GroupBody {
 
 #[derive(Clone)]
 pub struct Template {
+    /// The template whose region this defines, as in `@t.r() ::= ...`.
+    region_of: Option<Ident>,
     name: Ident,
     paren_token: token::Paren,
-    formal_args: Punctuated<Ident, Token![,]>,
+    formal_args: Punctuated<FormalArgument, Token![,]>,
     template_body: TemplateBody,
 }
 
 impl Template {
+    /// The name of the template in its group, which is `@t.r` for the
+    /// region `r` of `t`.
+    fn full_name(&self) -> String {
+        match &self.region_of {
+            Some(template) => format!("@{}.{}", template, self.name),
+            None => self.name.to_string(),
+        }
+    }
+
     pub fn access_fn(&self, vis: &Visibility) -> TokenStream {
+        // Regions aren't rendered on their own.
+        if self.region_of.is_some() {
+            return quote! {};
+        }
         let name = &self.name;
         let name_str = name.to_string();
         quote! {
@@ -225,14 +516,14 @@ impl Template {
 impl fmt::Debug for Template {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Template")
-            .field("name", &self.name)
+            .field("name", &self.full_name())
             .finish()
     }
 }
 
 impl cmp::PartialEq for Template {
     fn eq(&self, other: &Self) -> bool {
-        self.name == other.name
+        self.full_name() == other.full_name()
     }
 }
 
@@ -249,25 +540,37 @@ impl From<Template> for CompiledTemplate {
         } = template_body;
         CompiledTemplate::with_args(
             literal.value(),
-            formal_args.iter().map(Ident::to_string),
+            formal_args.into_iter().map(|arg| {
+                let default = arg.default.map(|default| default.expr);
+                (arg.name.to_string(), default)
+            }),
             expressions,
         )
     }
 }
 
-impl Parse for Template {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
+impl Template {
+    fn parse(input: ParseStream, delimiters: Delimiters) -> syn::Result<Self> {
+        let region_of = if input.peek(Token![@]) {
+            input.parse::<Token![@]>()?;
+            let template = input.parse()?;
+            input.parse::<Token![.]>()?;
+            Some(template)
+        } else {
+            None
+        };
         let name = input.parse()?;
         let content;
         let paren_token = parenthesized!(content in input);
-        let formal_args = content.parse_terminated(Ident::parse)?;
+        let formal_args = parse_terminated(&content, delimiters, FormalArgument::parse)?;
 
         input.parse::<Token![::]>()?;
         input.parse::<Token![=]>()?;
 
-        let template_body = input.parse()?;
+        let template_body = TemplateBody::parse(input, delimiters)?;
 
         Ok(Template {
+            region_of,
             name,
             paren_token,
             formal_args,
@@ -278,16 +581,22 @@ impl Parse for Template {
 
 impl ToTokens for Template {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        let name = self.name.to_string();
+        let name = self.full_name();
         let template_body = &self.template_body.to_string();
-        let formal_args = self.formal_args.iter().map(Ident::to_string);
+        let formal_args = self.formal_args.iter().map(|arg| {
+            let name = arg.name.to_string();
+            match &arg.default {
+                Some(DefaultValue { expr, .. }) => quote! { (#name.to_string(), Some(#expr)) },
+                None => quote! { (#name.to_string(), None) },
+            }
+        });
         let compiled_template = &self.template_body;
         let expanded = quote! {
             templates.insert(
                 #name.to_string(),
                 ::string_template::CompiledTemplate::with_args(
                     #template_body,
-                    vec![ #( #formal_args.to_string() ),* ],
+                    vec![ #( #formal_args ),* ],
                     #compiled_template
                 )
             );
@@ -296,6 +605,235 @@ impl ToTokens for Template {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+struct Dictionary {
+    name: Ident,
+    bracket_token: token::Bracket,
+    entries: Punctuated<DictionaryEntry, Token![,]>,
+}
+
+impl Dictionary {
+    fn parse(input: ParseStream, delimiters: Delimiters) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![::]>()?;
+        input.parse::<Token![=]>()?;
+        let content;
+        let bracket_token = bracketed!(content in input);
+        let entries = parse_terminated(&content, delimiters, DictionaryEntry::parse)?;
+        Ok(Dictionary {
+            name,
+            bracket_token,
+            entries,
+        })
+    }
+}
+
+impl From<Dictionary> for RuntimeDictionary {
+    fn from(static_dictionary: Dictionary) -> RuntimeDictionary {
+        let mut dictionary = RuntimeDictionary::new();
+        for entry in static_dictionary.entries {
+            match (entry.key, entry.value.expr) {
+                (None, None) => dictionary.set_default(DictionaryDefault::Key),
+                (None, Some(value)) => dictionary.set_default(DictionaryDefault::Value(value)),
+                (Some(key), None) => {
+                    let key = key.value();
+                    dictionary.insert(key.clone(), Expr::Literal(key))
+                }
+                (Some(key), Some(value)) => dictionary.insert(key.value(), value),
+            }
+        }
+        dictionary
+    }
+}
+
+impl ToTokens for Dictionary {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let name = self.name.to_string();
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| match (&entry.key, &entry.value.expr) {
+                (None, None) => quote! {
+                    dictionary.set_default(::string_template::DictionaryDefault::Key);
+                },
+                (None, Some(value)) => quote! {
+                    dictionary.set_default(::string_template::DictionaryDefault::Value(#value));
+                },
+                (Some(key), None) => quote! {
+                    dictionary.insert(#key, ::string_template::Expr::Literal(#key.to_string()));
+                },
+                (Some(key), Some(value)) => quote! {
+                    dictionary.insert(#key, #value);
+                },
+            });
+        let expanded = quote! {
+            group.add_dictionary(#name, {
+                let mut dictionary = ::string_template::Dictionary::new();
+                #( #entries )*
+                dictionary
+            });
+        };
+        tokens.extend(expanded);
+    }
+}
+
+/// An entry of a dictionary, as in `"int":"0"`, or its default value,
+/// as in `default:key`.
+#[derive(Clone, Debug, PartialEq)]
+struct DictionaryEntry {
+    key: Option<LitStr>,
+    value: DictionaryValue,
+}
+
+impl DictionaryEntry {
+    fn parse(input: ParseStream, delimiters: Delimiters) -> syn::Result<Self> {
+        let key = if input.peek(LitStr) {
+            Some(input.parse()?)
+        } else {
+            let ident: Ident = input.parse()?;
+            if ident != "default" {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "expected a string key or `default`",
+                ));
+            }
+            None
+        };
+        input.parse::<Token![:]>()?;
+        let value = DictionaryValue::parse(input, delimiters)?;
+        Ok(DictionaryEntry { key, value })
+    }
+}
+
+impl ToTokens for DictionaryEntry {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let value = &self.value.value;
+        let expanded = match &self.key {
+            Some(key) => quote! { #key : #value },
+            None => quote! { default : #value },
+        };
+        tokens.extend(expanded);
+    }
+}
+
+/// The value of a dictionary entry, which is either the same as a
+/// default argument value or `key` for the key itself.
+#[derive(Clone)]
+struct DictionaryValue {
+    value: TokenTree,
+    expr: Option<Expr>,
+}
+
+impl fmt::Debug for DictionaryValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DictionaryValue")
+            .field("expr", &self.expr)
+            .finish()
+    }
+}
+
+impl cmp::PartialEq for DictionaryValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.expr == other.expr
+    }
+}
+
+impl DictionaryValue {
+    fn parse(input: ParseStream, delimiters: Delimiters) -> syn::Result<Self> {
+        let value: TokenTree = input.parse()?;
+        let expr = match &value {
+            TokenTree::Ident(ident) if ident == "key" => None,
+            value => Some(default_value_expr(value, delimiters)?),
+        };
+        Ok(DictionaryValue { value, expr })
+    }
+}
+
+#[derive(Clone)]
+struct FormalArgument {
+    name: Ident,
+    default: Option<DefaultValue>,
+}
+
+impl FormalArgument {
+    fn parse(input: ParseStream, delimiters: Delimiters) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let default = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(DefaultValue::parse(input, delimiters)?)
+        } else {
+            None
+        };
+        Ok(FormalArgument { name, default })
+    }
+}
+
+impl ToTokens for FormalArgument {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let name = &self.name;
+        let expanded = match &self.default {
+            Some(DefaultValue { value, .. }) => quote! { #name = #value },
+            None => quote! { #name },
+        };
+        tokens.extend(expanded);
+    }
+}
+
+#[derive(Clone)]
+struct DefaultValue {
+    value: TokenTree,
+    expr: Expr,
+}
+
+impl DefaultValue {
+    fn parse(input: ParseStream, delimiters: Delimiters) -> syn::Result<Self> {
+        let value: TokenTree = input.parse()?;
+        let expr = default_value_expr(&value, delimiters)?;
+        Ok(DefaultValue { value, expr })
+    }
+}
+
+fn default_value_expr(value: &TokenTree, delimiters: Delimiters) -> syn::Result<Expr> {
+    match value {
+        TokenTree::Literal(_) => {
+            let literal: LitStr = syn::parse2(quote! { #value })?;
+            Ok(Expr::Literal(literal.value()))
+        }
+        TokenTree::Ident(ident) if ident == "true" => Ok(Expr::Bool(true)),
+        TokenTree::Ident(ident) if ident == "false" => Ok(Expr::Bool(false)),
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Bracket => {
+            let items =
+                Punctuated::<TokenTree, Token![,]>::parse_terminated.parse2(group.stream())?;
+            let items = items
+                .iter()
+                .map(|item| default_value_expr(item, delimiters));
+            Ok(Expr::List(items.collect::<syn::Result<_>>()?))
+        }
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+            let text = anonymous_template_text(group)?;
+            let expressions = TemplateParser::expressions_of(&text, delimiters)?;
+            Ok(Expr::SubTemplate(vec![], expressions))
+        }
+        _ => Err(syn::Error::new(
+            value.span(),
+            "expected a string, boolean, list or anonymous template",
+        )),
+    }
+}
+
+/// The text of an anonymous template default value, which is a string
+/// in braces, as in `{"<x> and <y>"}`, so that its whitespace is kept.
+fn anonymous_template_text(group: &proc_macro2::Group) -> syn::Result<String> {
+    syn::parse2::<LitStr>(group.stream())
+        .map(|literal| literal.value())
+        .map_err(|_| {
+            syn::Error::new(
+                group.span(),
+                "expected the anonymous template as a string, as in `{\"<x>\"}`",
+            )
+        })
+}
+
 #[derive(Clone)]
 struct TemplateBody {
     literal: syn::LitStr,
@@ -320,10 +858,10 @@ impl cmp::PartialEq for TemplateBody {
     }
 }
 
-impl Parse for TemplateBody {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
+impl TemplateBody {
+    fn parse(input: ParseStream, delimiters: Delimiters) -> syn::Result<Self> {
         let literal: syn::LitStr = input.parse()?;
-        let expressions = TemplateParser::expressions_of(&literal.value())?;
+        let expressions = TemplateParser::expressions_of(&literal.value(), delimiters)?;
         Ok(TemplateBody {
             literal,
             expressions,
@@ -348,6 +886,9 @@ impl ToTokens for Expr {
             Expr::Literal(content) => {
                 quote! { ::string_template::Expr::Literal(#content.to_string()) }
             }
+            Expr::String(content) => {
+                quote! { ::string_template::Expr::String(#content.to_string()) }
+            }
             Expr::Attribute(name) => {
                 quote! { ::string_template::Expr::Attribute(#name.to_string()) }
             }
@@ -359,9 +900,153 @@ impl ToTokens for Expr {
                     )
                 }
             }
-            Expr::Include(name, arg_names) => {
-                quote! { ::string_template::Expr::Include(#name.to_string(), vec![ #( #arg_names ),* ]) }
+            Expr::IndirectProperty(object, property) => {
+                quote! {
+                    ::string_template::Expr::IndirectProperty(
+                        Box::new(#object),
+                        Box::new(#property)
+                    )
+                }
+            }
+            Expr::Include(name, arguments) => {
+                quote! { ::string_template::Expr::Include(#name.to_string(), #arguments) }
+            }
+            Expr::IndirectInclude(name, arguments) => {
+                quote! {
+                    ::string_template::Expr::IndirectInclude(Box::new(#name), #arguments)
+                }
+            }
+            Expr::If(condition, consequent, alternative) => {
+                quote! {
+                    ::string_template::Expr::If(
+                        Box::new(#condition),
+                        vec![ #( #consequent ),* ],
+                        vec![ #( #alternative ),* ]
+                    )
+                }
+            }
+            Expr::Not(operand) => {
+                quote! { ::string_template::Expr::Not(Box::new(#operand)) }
+            }
+            Expr::And(lhs, rhs) => {
+                quote! { ::string_template::Expr::And(Box::new(#lhs), Box::new(#rhs)) }
+            }
+            Expr::Or(lhs, rhs) => {
+                quote! { ::string_template::Expr::Or(Box::new(#lhs), Box::new(#rhs)) }
+            }
+            Expr::SubTemplate(parameters, body) => {
+                quote! {
+                    ::string_template::Expr::SubTemplate(
+                        vec![ #( #parameters.to_string() ),* ],
+                        vec![ #( #body ),* ]
+                    )
+                }
+            }
+            Expr::Map(value, template) => {
+                quote! { ::string_template::Expr::Map(Box::new(#value), Box::new(#template)) }
+            }
+            Expr::RoundRobinMap(value, templates) => {
+                quote! {
+                    ::string_template::Expr::RoundRobinMap(
+                        Box::new(#value),
+                        vec![ #( #templates ),* ]
+                    )
+                }
+            }
+            Expr::ParallelMap(values, template) => {
+                quote! {
+                    ::string_template::Expr::ParallelMap(
+                        vec![ #( #values ),* ],
+                        Box::new(#template)
+                    )
+                }
+            }
+            Expr::Bool(value) => {
+                quote! { ::string_template::Expr::Bool(#value) }
             }
+            Expr::List(items) => {
+                quote! { ::string_template::Expr::List(vec![ #( #items ),* ]) }
+            }
+            Expr::Region(name, default) => {
+                quote! {
+                    ::string_template::Expr::Region(
+                        #name.to_string(),
+                        vec![ #( #default ),* ]
+                    )
+                }
+            }
+            Expr::Function(function, argument) => {
+                quote! { ::string_template::Expr::Function(#function, Box::new(#argument)) }
+            }
+            Expr::Options(expr, options) => {
+                let names = options.iter().map(|(name, _)| name);
+                let values = options.iter().map(|(_, value)| value);
+                quote! {
+                    ::string_template::Expr::Options(
+                        Box::new(#expr),
+                        vec![ #( (#names, #values) ),* ]
+                    )
+                }
+            }
+        };
+        tokens.extend(expanded);
+    }
+}
+
+impl ToTokens for Arguments {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let expanded = match self {
+            Arguments::Positional(values) => {
+                quote! { ::string_template::Arguments::Positional(vec![ #( #values ),* ]) }
+            }
+            Arguments::Named(values) => {
+                let names = values.iter().map(|(name, _)| name);
+                let values = values.iter().map(|(_, value)| value);
+                quote! {
+                    ::string_template::Arguments::Named(
+                        vec![ #( (#names.to_string(), #values) ),* ]
+                    )
+                }
+            }
+            Arguments::PassThrough(values) => {
+                let names = values.iter().map(|(name, _)| name);
+                let values = values.iter().map(|(_, value)| value);
+                quote! {
+                    ::string_template::Arguments::PassThrough(
+                        vec![ #( (#names.to_string(), #values) ),* ]
+                    )
+                }
+            }
+        };
+        tokens.extend(expanded);
+    }
+}
+
+impl ToTokens for ExprOption {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let expanded = match self {
+            ExprOption::Anchor => quote! { ::string_template::ExprOption::Anchor },
+            ExprOption::Format => quote! { ::string_template::ExprOption::Format },
+            ExprOption::Null => quote! { ::string_template::ExprOption::Null },
+            ExprOption::Separator => quote! { ::string_template::ExprOption::Separator },
+            ExprOption::Wrap => quote! { ::string_template::ExprOption::Wrap },
+        };
+        tokens.extend(expanded);
+    }
+}
+
+impl ToTokens for Function {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let expanded = match self {
+            Function::First => quote! { ::string_template::Function::First },
+            Function::Last => quote! { ::string_template::Function::Last },
+            Function::Rest => quote! { ::string_template::Function::Rest },
+            Function::Trunc => quote! { ::string_template::Function::Trunc },
+            Function::Length => quote! { ::string_template::Function::Length },
+            Function::Reverse => quote! { ::string_template::Function::Reverse },
+            Function::Strip => quote! { ::string_template::Function::Strip },
+            Function::Trim => quote! { ::string_template::Function::Trim },
+            Function::Strlen => quote! { ::string_template::Function::Strlen },
         };
         tokens.extend(expanded);
     }
@@ -380,29 +1065,99 @@ impl AsDynamicTemplate for Group {
 
 impl AsDynamicTemplate for GroupBody {
     fn as_dynamic_template(&self) -> TokenStream {
-        let template_str: String = self
-            .templates
+        let Delimiters { start, stop } = self.delimiters;
+        let template_str: String = Some(format!(
+            "delimiters {}, {}",
+            quoted(&start.to_string()),
+            quoted(&stop.to_string())
+        ))
+        .into_iter()
+        .chain(
+            self.imports
+                .iter()
+                .map(|import| format!("import {}", quoted(&import.path.value()))),
+        )
+        .chain(self.templates.iter().map(Template::group_text))
+        .chain(self.dictionaries.iter().map(Dictionary::group_text))
+        .map(|definition| format!("{}\n", definition))
+        .collect();
+        quote! { #template_str }
+    }
+}
+
+impl Template {
+    /// The definition of this template in a group file.
+    fn group_text(&self) -> String {
+        let formal_args: Vec<_> = self
+            .formal_args
             .iter()
-            .map(Template::as_dynamic_template)
-            .map(|tokens| format!("{}\n", tokens))
+            .map(|arg| match &arg.default {
+                Some(DefaultValue { value, .. }) => {
+                    format!("{}={}", arg.name, default_value_text(value))
+                }
+                None => arg.name.to_string(),
+            })
             .collect();
-        quote! { #template_str }
+        format!(
+            "{}({}) ::= {}",
+            self.full_name(),
+            formal_args.join(", "),
+            quoted(&self.template_body.literal.value())
+        )
     }
 }
 
-impl AsDynamicTemplate for Template {
-    fn as_dynamic_template(&self) -> TokenStream {
-        let name = &self.name;
-        let formal_args = &self.formal_args;
-        let template_body = self.template_body.as_dynamic_template();
-        quote! { #name ( #( #formal_args ),* ) ::= #template_body }
+impl Dictionary {
+    /// The definition of this dictionary in a group file.
+    fn group_text(&self) -> String {
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let key = match &entry.key {
+                    Some(key) => quoted(&key.value()),
+                    None => "default".to_string(),
+                };
+                let value = match &entry.value.expr {
+                    Some(_) => default_value_text(&entry.value.value),
+                    None => "key".to_string(),
+                };
+                format!("{}:{}", key, value)
+            })
+            .collect();
+        format!("{} ::= [{}]", self.name, entries.join(", "))
     }
 }
 
-impl AsDynamicTemplate for TemplateBody {
-    fn as_dynamic_template(&self) -> TokenStream {
-        let literal = &self.literal;
-        quote! { #literal }
+/// `text` as a string in a group file.
+fn quoted(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+/// The text of a default value in a group file, like
+/// `default_value_expr` parses it.
+fn default_value_text(value: &TokenTree) -> String {
+    match value {
+        TokenTree::Literal(_) => match syn::parse2::<LitStr>(quote! { #value }) {
+            Ok(literal) => quoted(&literal.value()),
+            Err(_) => value.to_string(),
+        },
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Bracket => {
+            let items = Punctuated::<TokenTree, Token![,]>::parse_terminated
+                .parse2(group.stream())
+                .map(|items| items.iter().map(default_value_text).collect::<Vec<_>>())
+                .unwrap_or_default();
+            format!("[{}]", items.join(", "))
+        }
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+            let text = anonymous_template_text(group).unwrap_or_default();
+            format!("{{{}}}", quoted(&text))
+        }
+        value => value.to_string(),
     }
 }
 
@@ -433,4 +1188,15 @@ mod tests {
             ),
         );
     }
+
+    #[test]
+    fn errors_parsing_anonymous_template_defaults_written_as_tokens() {
+        assert!(r#"t(x, y, body={<x> and <y>}) ::= "<body>""#.parse::<GroupBody>().is_err());
+    }
+
+    #[test]
+    fn errors_importing_missing_group_file() {
+        let error = r#"import "missing.stg""#.parse::<GroupBody>().unwrap_err();
+        assert!(error.to_string().contains("failed to import missing.stg"));
+    }
 }
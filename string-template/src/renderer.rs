@@ -0,0 +1,93 @@
+use serde_json::Value as Json;
+
+/// The locale used when rendering without one.
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// The kinds of attribute value that renderers are registered for.
+///
+/// Lists are rendered element by element and nulls aren't rendered,
+/// so they have no kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    Bool,
+    Number,
+    String,
+    Object,
+}
+
+impl ValueKind {
+    pub fn of(value: &Json) -> Option<ValueKind> {
+        match value {
+            Json::Bool(_) => Some(ValueKind::Bool),
+            Json::Number(_) => Some(ValueKind::Number),
+            Json::String(_) => Some(ValueKind::String),
+            Json::Object(_) => Some(ValueKind::Object),
+            Json::Null | Json::Array(_) => None,
+        }
+    }
+}
+
+/// Turns attribute values of one kind into text, in place of the
+/// default rendering.
+///
+/// Renderers are registered on a group with `Group::register_renderer`.
+pub trait AttributeRenderer {
+    /// Render `value`, given the `format` option of the expression,
+    /// as in `<price; format="%.2f">`, and the locale being rendered
+    /// for, as a language tag like `en-US`.
+    fn render(&self, value: &Json, format: Option<&str>, locale: &str) -> String;
+}
+
+/// The renderer for strings used when a group has none registered,
+/// which understands the formats `upper`, `lower`, `cap`, `url-encode`
+/// and `xml-encode`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StringRenderer;
+
+impl AttributeRenderer for StringRenderer {
+    fn render(&self, value: &Json, format: Option<&str>, _locale: &str) -> String {
+        let string = match value {
+            Json::String(string) => string,
+            value => return value.to_string(),
+        };
+        match format {
+            Some("upper") => string.to_uppercase(),
+            Some("lower") => string.to_lowercase(),
+            Some("cap") => {
+                let mut chars = string.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            }
+            Some("url-encode") => {
+                let mut encoded = String::with_capacity(string.len());
+                for byte in string.bytes() {
+                    match byte {
+                        b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'*' => {
+                            encoded.push(byte as char)
+                        }
+                        b' ' => encoded.push('+'),
+                        byte => encoded.push_str(&format!("%{:02X}", byte)),
+                    }
+                }
+                encoded
+            }
+            Some("xml-encode") => {
+                let mut encoded = String::with_capacity(string.len());
+                for c in string.chars() {
+                    match c {
+                        '&' => encoded.push_str("&amp;"),
+                        '<' => encoded.push_str("&lt;"),
+                        '>' => encoded.push_str("&gt;"),
+                        '"' => encoded.push_str("&quot;"),
+                        '\'' => encoded.push_str("&apos;"),
+                        c => encoded.push(c),
+                    }
+                }
+                encoded
+            }
+            _ => string.to_string(),
+        }
+    }
+}
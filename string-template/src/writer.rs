@@ -0,0 +1,199 @@
+use std::fmt;
+
+/// The writer the interpreter renders templates through, which knows
+/// how far the expression being rendered is indented.
+///
+/// The interpreter pushes the whitespace preceding an expression on its
+/// line before rendering it, and pops it afterwards, so that a writer
+/// can indent every line of a multi-line value by the same amount.
+pub trait StWriter: fmt::Write {
+    /// Start indenting by `indent` as well as the indentation already
+    /// pushed.
+    fn push_indentation(&mut self, indent: &str);
+
+    /// Stop indenting by the indentation pushed last.
+    fn pop_indentation(&mut self);
+
+    /// Line up lines started by wrapping with the current column, as
+    /// the `anchor` option does, until the anchor point is popped.
+    fn push_anchor_point(&mut self);
+
+    fn pop_anchor_point(&mut self);
+
+    /// Write `wrap` if writing `width` more characters would take the
+    /// current line past the line width. Each newline in `wrap` is
+    /// followed by the indentation, or by spaces up to the anchor
+    /// column if that's further along.
+    fn write_wrap(&mut self, wrap: &str, width: usize) -> fmt::Result;
+
+    /// Wrap lines at `width` columns, rather than never wrapping them.
+    fn set_line_width(&mut self, width: usize);
+
+    /// Whether nothing has been written on the current line yet, so
+    /// whitespace written next is indentation.
+    fn at_start_of_line(&self) -> bool;
+}
+
+/// Creates the writer that output is rendered through, given where the
+/// output goes.
+pub type NewWriter = for<'a> fn(&'a mut dyn fmt::Write) -> Box<dyn StWriter + 'a>;
+
+/// Writes the indentation pushed so far at the start of every line,
+/// so that templates rendered by an indented expression line up with
+/// it, and keeps track of the column for wrapping.
+pub struct AutoIndentWriter<'a> {
+    out: &'a mut dyn fmt::Write,
+    indents: Vec<String>,
+    anchors: Vec<usize>,
+    at_start_of_line: bool,
+    column: usize,
+    line_width: Option<usize>,
+}
+
+impl<'a> AutoIndentWriter<'a> {
+    pub fn new(out: &'a mut dyn fmt::Write) -> AutoIndentWriter<'a> {
+        AutoIndentWriter {
+            out,
+            indents: Vec::new(),
+            anchors: Vec::new(),
+            at_start_of_line: true,
+            column: 0,
+            line_width: None,
+        }
+    }
+
+    /// Write the indentation at the start of a line, and then spaces up
+    /// to the anchor column if it's further along.
+    fn indent(&mut self) -> fmt::Result {
+        let mut width = 0;
+        for indent in &self.indents {
+            self.out.write_str(indent)?;
+            width += indent.chars().count();
+        }
+        if let Some(&anchor) = self.anchors.last() {
+            for _ in width..anchor {
+                self.out.write_char(' ')?;
+            }
+            width = width.max(anchor);
+        }
+        self.column = width;
+        Ok(())
+    }
+}
+
+impl AutoIndentWriter<'_> {
+    /// Create a writer to `out` for `Interpreter::with_writer`.
+    pub fn boxed(out: &mut dyn fmt::Write) -> Box<dyn StWriter + '_> {
+        Box::new(AutoIndentWriter::new(out))
+    }
+}
+
+impl<'a> fmt::Write for AutoIndentWriter<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for line in s.split_inclusive('\n') {
+            // Blank lines aren't indented.
+            if self.at_start_of_line && line != "\n" && line != "\r\n" {
+                self.indent()?;
+            }
+            self.out.write_str(line)?;
+            self.at_start_of_line = line.ends_with('\n');
+            if self.at_start_of_line {
+                self.column = 0;
+            } else {
+                self.column += line.chars().count();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> StWriter for AutoIndentWriter<'a> {
+    fn push_indentation(&mut self, indent: &str) {
+        self.indents.push(indent.to_owned());
+    }
+
+    fn pop_indentation(&mut self) {
+        self.indents.pop();
+    }
+
+    fn push_anchor_point(&mut self) {
+        self.anchors.push(self.column);
+    }
+
+    fn pop_anchor_point(&mut self) {
+        self.anchors.pop();
+    }
+
+    fn write_wrap(&mut self, wrap: &str, width: usize) -> fmt::Result {
+        let line_width = match self.line_width {
+            Some(line_width) => line_width,
+            None => return Ok(()),
+        };
+        if self.at_start_of_line || self.column + width <= line_width {
+            return Ok(());
+        }
+        for c in wrap.chars().filter(|&c| c != '\r') {
+            if c == '\n' {
+                self.out.write_char('\n')?;
+                self.indent()?;
+            } else {
+                self.out.write_char(c)?;
+                self.column += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn set_line_width(&mut self, width: usize) {
+        self.line_width = Some(width);
+    }
+
+    fn at_start_of_line(&self) -> bool {
+        self.at_start_of_line
+    }
+}
+
+/// Writes the output as it's rendered, without indenting or wrapping
+/// it.
+pub struct NoIndentWriter<'a> {
+    out: &'a mut dyn fmt::Write,
+}
+
+impl<'a> NoIndentWriter<'a> {
+    pub fn new(out: &'a mut dyn fmt::Write) -> NoIndentWriter<'a> {
+        NoIndentWriter { out }
+    }
+}
+
+impl NoIndentWriter<'_> {
+    /// Create a writer to `out` for `Interpreter::with_writer`.
+    pub fn boxed(out: &mut dyn fmt::Write) -> Box<dyn StWriter + '_> {
+        Box::new(NoIndentWriter::new(out))
+    }
+}
+
+impl<'a> fmt::Write for NoIndentWriter<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.out.write_str(s)
+    }
+}
+
+impl<'a> StWriter for NoIndentWriter<'a> {
+    fn push_indentation(&mut self, _indent: &str) {}
+
+    fn pop_indentation(&mut self) {}
+
+    fn push_anchor_point(&mut self) {}
+
+    fn pop_anchor_point(&mut self) {}
+
+    fn write_wrap(&mut self, _wrap: &str, _width: usize) -> fmt::Result {
+        Ok(())
+    }
+
+    fn set_line_width(&mut self, _width: usize) {}
+
+    fn at_start_of_line(&self) -> bool {
+        false
+    }
+}